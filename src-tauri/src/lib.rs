@@ -49,17 +49,32 @@ mod modules;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    crate::modules::health::health::mark_started();
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         // Register Tauri commands here as modules are added
             .invoke_handler(tauri::generate_handler![
                 crate::modules::log_reader::log_reader::start_log_reader,
                 crate::modules::log_reader::log_reader::stop_log_reader,
+                crate::modules::log_reader::log_reader::set_log_poll_interval,
+                crate::modules::log_reader::log_reader::get_watcher_status,
                 crate::modules::log_reader::log_reader::get_most_recent_log_file,
+                crate::modules::log_reader::line_index::get_log_line_count,
+                crate::modules::log_reader::line_index::read_log_lines,
                 crate::modules::log_reader::log_reader::open_most_recent_log_file,
                 crate::modules::log_reader::log_reader::open_most_recent_log_folder,
                 crate::modules::log_reader::log_parser::manual_refresh_scan,
+                crate::modules::log_reader::log_parser::backfill_current_instance,
+                crate::modules::log_reader::log_parser::analyze_log_file,
+                crate::modules::log_reader::log_parser::get_authenticated_user,
+                crate::modules::log_reader::log_parser::set_player_event_batching,
                 crate::modules::log_reader::log_parser::get_current_location,
+                crate::modules::log_reader::log_parser::get_current_instance,
+                crate::modules::log_reader::log_reader::resync_live_view,
+                crate::modules::health::health::app_heartbeat,
+                crate::modules::vrchat_process::vrchat_process::is_vrchat_running,
+                crate::modules::vrchat_process::vrchat_process::set_auto_start_watcher,
                 crate::modules::log_reader::log_parser::get_instance_history,
                 crate::modules::local_db::localdb::add_note,
                 crate::modules::local_db::localdb::get_note,
@@ -70,27 +85,86 @@ pub fn run() {
                 crate::modules::local_db::localdb::set_user_sound,
                 crate::modules::local_db::localdb::get_user_sound,
                 crate::modules::local_db::localdb::set_username,
+                crate::modules::local_db::localdb::add_watch_by_id,
+                crate::modules::local_db::user_summary::copy_user_summary,
+                crate::modules::image_cache::image_cache::cache_image,
+                crate::modules::image_cache::image_cache::clear_image_cache,
                 crate::modules::local_db::localdb::browse_sound,
                 crate::modules::world_mod::world_mod_logs::add_ban_log_entry,
                 crate::modules::world_mod::world_mod_logs::get_all_ban_log_entries,
                 crate::modules::world_mod::world_mod_logs::search_ban_log_entries,
+                crate::modules::world_mod::world_mod_logs::get_admin_moderation_stats,
+                crate::modules::world_mod::world_mod_logs::repair_timestamps,
                 crate::modules::group_auth::group_access_tokens::add_group_access_token,
                 crate::modules::group_auth::group_access_tokens::list_group_access_tokens,
                 crate::modules::group_auth::group_access_tokens::remove_group_access_token,
                 crate::modules::instance_monitor::batcher::add_user_to_batch_command,
+                crate::modules::instance_monitor::batcher::check_user_groups,
+                crate::modules::instance_monitor::batcher::set_group_check_batching,
+                crate::modules::instance_monitor::batcher::get_group_check_stats,
+                crate::modules::http_api::http_api::set_http_api,
+                crate::modules::http_api::http_api::get_http_api_status,
+                crate::modules::http_api::http_api::regenerate_http_api_token,
+                crate::modules::instance_monitor::group_matches::get_active_group_matches,
+                crate::modules::instance_monitor::group_matches::get_group_watchlisted_history,
                 crate::modules::instance_monitor::batcher::flush_user_batch,
                 crate::modules::settings::settings::get_settings,
                 crate::modules::settings::settings::set_master_volume,
+                crate::modules::settings::settings::set_notifications_muted,
+                crate::modules::settings::settings::set_normalize_sounds,
+                crate::modules::settings::settings::set_suppress_self_events,
+                crate::modules::settings::settings::set_api_base_override,
+                crate::modules::settings::settings::get_api_base,
                 crate::modules::settings::settings::set_group_notification_settings,
                 crate::modules::settings::settings::set_local_notification_settings,
+                crate::modules::settings::settings::set_log_timezone,
+                crate::modules::settings::settings::set_backfill_scan_bytes,
+                crate::modules::debug::log_file::set_debug_log_file,
+                crate::modules::log_reader::event_exporter::set_event_export,
+                crate::modules::debug::debug_log::get_recent_debug,
+                crate::modules::log_reader::log_parser::inject_log_lines,
+                crate::modules::log_reader::session_markers::get_session_markers,
+                crate::modules::log_reader::session_markers::set_session_markers,
+                crate::modules::log_reader::rejoin_storm::set_rejoin_storm_config,
+                crate::modules::log_reader::join_grace::set_join_grace_secs,
                 crate::modules::sound::sound::play_user_notification_sound,
+                crate::modules::sound::sound::stop_all_sounds,
+                crate::modules::sound::sound::get_effective_notification_config,
+                crate::modules::sound::sound::validate_sound_file,
                 crate::modules::sound::sound::preview_group_notification_sound,
                 crate::modules::sound::sound::preview_local_notification_sound,
+                crate::modules::notifications::toast::show_group_match_toast,
+                crate::modules::backup::backup::backup_data,
+                crate::modules::backup::backup::restore_data,
+                crate::modules::database::integrity::check_database_integrity,
+                crate::modules::database::integrity::recover_database,
+                crate::modules::database::stats::get_database_stats,
+                crate::modules::database::search::global_search,
+                crate::modules::watchlist::watchlist_hits::list_watchlist_hits,
+                crate::modules::history::first_seen::get_first_seen,
+                crate::modules::history::session_labels::set_session_label,
+                crate::modules::history::session_labels::list_session_labels,
+                crate::modules::history::session_rosters::get_session_roster,
+                crate::modules::history::session_rosters::list_session_rosters,
+                crate::modules::history::instance_visits::get_region_stats,
+                crate::modules::history::instance_visits::list_visited_worlds,
+                crate::modules::history::favorite_worlds::add_favorite_world,
+                crate::modules::history::favorite_worlds::remove_favorite_world,
+                crate::modules::history::favorite_worlds::list_favorite_worlds,
+                crate::modules::history::favorite_worlds::open_world_page,
                 crate::modules::updater::updater::check_for_update,
                 crate::modules::updater::updater::download_update,
+                crate::modules::updater::updater::cancel_update_download,
+                crate::modules::updater::updater::get_version_status,
+                crate::modules::updater::updater::get_app_version,
+                crate::modules::updater::updater::list_available_rollbacks,
+                crate::modules::updater::updater::rollback_to_previous,
                 crate::modules::updater::updater::run_installer,
                 crate::modules::updater::updater::download_and_install_update,
             ])
+        .on_window_event(|window, event| {
+            crate::modules::window_state::window_state::on_window_event(window, event);
+        })
         .setup(|app| {
             // Initialize modules here
             // Initialize notes database - create file if it doesn't exist
@@ -105,6 +179,43 @@ pub fn run() {
             if let Err(err) = crate::modules::group_auth::group_access_tokens::init_db() {
                 crate::debug_eprintln!("failed to initialize group access tokens database: {err:?}");
             }
+            // Initialize watchlist hit history table
+            if let Err(err) = crate::modules::watchlist::watchlist_hits::init_db() {
+                crate::debug_eprintln!("failed to initialize watchlist hits table: {err:?}");
+            }
+            // Initialize first-seen table
+            if let Err(err) = crate::modules::history::first_seen::init_db() {
+                crate::debug_eprintln!("failed to initialize first seen table: {err:?}");
+            }
+            // Initialize group match table
+            if let Err(err) = crate::modules::instance_monitor::group_matches::init_db() {
+                crate::debug_eprintln!("failed to initialize group matches table: {err:?}");
+            }
+            // Initialize session labels table
+            if let Err(err) = crate::modules::history::session_labels::init_db() {
+                crate::debug_eprintln!("failed to initialize session labels table: {err:?}");
+            }
+            // Initialize session rosters table
+            if let Err(err) = crate::modules::history::session_rosters::init_db() {
+                crate::debug_eprintln!("failed to initialize session rosters table: {err:?}");
+            }
+            // Initialize instance visits table
+            if let Err(err) = crate::modules::history::instance_visits::init_db() {
+                crate::debug_eprintln!("failed to initialize instance visits table: {err:?}");
+            }
+            // Initialize favorite worlds table
+            if let Err(err) = crate::modules::history::favorite_worlds::init_db() {
+                crate::debug_eprintln!("failed to initialize favorite worlds table: {err:?}");
+            }
+            // Check database health in the background (emits database_unhealthy on problems)
+            crate::modules::database::integrity::check_on_startup(app.handle().clone());
+            // Remember the running version so a downgrade can be detected
+            crate::modules::updater::updater::record_running_version(crate::modules::updater::updater::app_version());
+            // Apply settings modules cache in memory (timezone, rejoin storm, join grace, debug
+            // log file, events.jsonl export, local HTTP API, ...)
+            crate::modules::settings::settings::reload_all_from_settings();
+            // Follow the VRChat process with the log reader if the user enabled it
+            crate::modules::vrchat_process::vrchat_process::init(app.handle().clone());
             // Initialize group watchlist batcher
             if let Err(err) = crate::modules::instance_monitor::batcher::init_batcher(app.handle().clone()) {
                 crate::debug_eprintln!("failed to initialize group watchlist batcher: {err:?}");
@@ -113,6 +224,12 @@ pub fn run() {
             if let Err(err) = crate::modules::settings::settings::init_settings() {
                 crate::debug_eprintln!("failed to initialize settings: {err:?}");
             }
+            // Restore the saved window size/position, then show the (initially hidden) window
+            crate::modules::window_state::window_state::restore(app);
+            // System tray icon (mute / watcher / window toggles)
+            if let Err(err) = crate::modules::tray::tray::init_tray(app) {
+                crate::debug_eprintln!("failed to initialize tray icon: {err:?}");
+            }
             Ok(())
         })
         .run(tauri::generate_context!())
@@ -0,0 +1,78 @@
+// App State: Small key/value store for values the app learns at runtime
+//
+// Unlike settings.json (user choices), app_state.json holds things the backend
+// discovers on its own, e.g. the authenticated VRChat user id from the logs.
+// Values are cached in memory after the first read so hot paths (per log line)
+// don't hit the disk.
+
+use std::collections::BTreeMap;
+use std::{fs, path::PathBuf};
+use std::sync::Mutex;
+use lazy_static::lazy_static;
+use crate::modules::database::connection;
+
+lazy_static! {
+    static ref STATE: Mutex<Option<BTreeMap<String, String>>> = Mutex::new(None);
+}
+
+/// Get the path to the app_state.json file
+fn state_path() -> PathBuf {
+    connection::data_dir().join("app_state.json")
+}
+
+/// Load app state from disk (empty on missing/corrupt file)
+fn load_state() -> BTreeMap<String, String> {
+    let path = state_path();
+    if !path.exists() {
+        return BTreeMap::new();
+    }
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            crate::debug_eprintln!("[app_state] Failed to parse app_state.json: {}", e);
+            BTreeMap::new()
+        }),
+        Err(e) => {
+            crate::debug_eprintln!("[app_state] Failed to read app_state.json: {}", e);
+            BTreeMap::new()
+        }
+    }
+}
+
+/// Save app state to disk
+fn save_state(state: &BTreeMap<String, String>) -> Result<(), String> {
+    let path = state_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create app state directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize app state: {}", e))?;
+    fs::write(&path, json)
+        .map_err(|e| format!("Failed to write app_state.json: {}", e))
+}
+
+/// Get a value by key
+pub fn get(key: &str) -> Option<String> {
+    let mut guard = STATE.lock().ok()?;
+    guard.get_or_insert_with(load_state).get(key).cloned()
+}
+
+/// Set (Some) or remove (None) a value. Only writes to disk when the value changes.
+pub fn set(key: &str, value: Option<&str>) -> Result<(), String> {
+    let mut guard = STATE.lock().map_err(|_| "Failed to lock app state".to_string())?;
+    let state = guard.get_or_insert_with(load_state);
+    let changed = match value {
+        Some(v) => state.insert(key.to_string(), v.to_string()).as_deref() != Some(v),
+        None => state.remove(key).is_some(),
+    };
+    if changed {
+        save_state(state)?;
+    }
+    Ok(())
+}
+
+/// Drop the in-memory cache so the next read comes from disk (e.g. after a restore)
+pub fn invalidate() {
+    if let Ok(mut guard) = STATE.lock() {
+        *guard = None;
+    }
+}
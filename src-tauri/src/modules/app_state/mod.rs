@@ -0,0 +1,2 @@
+// App State module
+pub mod app_state;
@@ -0,0 +1,158 @@
+// Backup: Zip up and restore the app data folder (FCHClient)
+//
+// Only top-level files are included (notes, settings, app state, databases).
+// SQLite databases are copied with VACUUM INTO, so the backup is a consistent
+// snapshot even while the log watcher is writing; WAL/SHM/journal side files
+// are skipped. Restoring pauses the log watcher while files are replaced.
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::modules::app_state::app_state;
+use crate::modules::database::connection;
+use crate::modules::log_reader::log_reader;
+
+/// Files that belong in a backup (skip SQLite side files and other backups)
+fn is_backup_candidate(name: &str) -> bool {
+    !(name.ends_with("-wal") || name.ends_with("-shm") || name.ends_with("-journal") || name.ends_with(".zip"))
+}
+
+/// Copy a SQLite database to a temp file with VACUUM INTO (consistent snapshot)
+fn snapshot_db(path: &Path, name: &str) -> Result<PathBuf, String> {
+    let temp = std::env::temp_dir().join(format!("fch-backup-{}-{}", std::process::id(), name));
+    let _ = fs::remove_file(&temp);
+    let conn = crate::modules::database::connection::open_connection(path)
+        .map_err(|e| format!("Failed to open {}: {}", name, e))?;
+    conn.execute("VACUUM INTO ?1", [temp.to_string_lossy().to_string()])
+        .map_err(|e| format!("Failed to snapshot {}: {}", name, e))?;
+    Ok(temp)
+}
+
+/// Zip the data folder. Without a path the archive goes to FCHClient/backups/.
+/// Returns the archive path.
+#[tauri::command]
+pub fn backup_data(path: Option<String>) -> Result<String, String> {
+    let dir = connection::data_dir();
+    let target = match path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) {
+        Some(p) => PathBuf::from(p),
+        None => dir.join("backups").join(format!(
+            "FCHClient-backup-{}.zip",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        )),
+    };
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    }
+    
+    let entries = fs::read_dir(&dir).map_err(|e| format!("Failed to read data directory: {}", e))?;
+    let file = File::create(&target).map_err(|e| format!("Failed to create backup file: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !path.is_file() || path == target {
+            continue;
+        }
+        let Some(name) = path.file_name().and_then(|n| n.to_str()).map(|n| n.to_string()) else {
+            continue;
+        };
+        if !is_backup_candidate(&name) {
+            continue;
+        }
+        
+        let source = if name.ends_with(".db") { snapshot_db(&path, &name)? } else { path.clone() };
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to backup: {}", name, e))?;
+        let copied = File::open(&source)
+            .and_then(|mut f| std::io::copy(&mut f, &mut zip))
+            .map_err(|e| format!("Failed to add {} to backup: {}", name, e));
+        if source != path {
+            let _ = fs::remove_file(&source);
+        }
+        copied?;
+    }
+    
+    zip.finish().map_err(|e| format!("Failed to finish backup: {}", e))?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Restore a backup archive into the data folder. Refuses to replace existing files unless
+/// overwrite is true (the UI confirms first). Returns the restored file names.
+#[tauri::command]
+pub fn restore_data(app_handle: AppHandle, path: String, overwrite: Option<bool>) -> Result<Vec<String>, String> {
+    let dir = connection::data_dir();
+    let file = File::open(&path).map_err(|e| format!("Failed to open backup {}: {}", path, e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Invalid backup archive: {}", e))?;
+    
+    // Only plain top-level file names are accepted (no directories, no ../ tricks)
+    let mut names: Vec<(usize, String)> = Vec::new();
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i).map_err(|e| format!("Invalid backup archive: {}", e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry
+            .enclosed_name()
+            .filter(|p| p.components().count() == 1)
+            .and_then(|p| p.to_str().map(|s| s.to_string()));
+        if let Some(name) = name.filter(|n| is_backup_candidate(n)) {
+            names.push((i, name));
+        }
+    }
+    if names.is_empty() {
+        return Err("Backup archive contains no data files".to_string());
+    }
+    
+    if !overwrite.unwrap_or(false) {
+        let existing: Vec<&str> = names
+            .iter()
+            .filter(|(_, n)| dir.join(n).exists())
+            .map(|(_, n)| n.as_str())
+            .collect();
+        if !existing.is_empty() {
+            return Err(format!(
+                "Restoring would overwrite: {}. Confirm to overwrite.",
+                existing.join(", ")
+            ));
+        }
+    }
+    
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    
+    // Pause the watcher so nothing writes to the databases while they're replaced
+    let was_running = log_reader::is_log_reader_running();
+    if was_running {
+        log_reader::stop_log_reader()?;
+    }
+    
+    let result = (|| -> Result<Vec<String>, String> {
+        let mut restored = Vec::new();
+        for (index, name) in &names {
+            let mut entry = archive.by_index(*index).map_err(|e| format!("Invalid backup archive: {}", e))?;
+            let dest = dir.join(name);
+            let partial = dir.join(format!("{}.restoring", name));
+            let mut out = File::create(&partial).map_err(|e| format!("Failed to write {}: {}", name, e))?;
+            std::io::copy(&mut entry, &mut out).map_err(|e| format!("Failed to write {}: {}", name, e))?;
+            drop(out);
+            if name.ends_with(".db") {
+                // A stale WAL from the old database must not be replayed onto the restored one
+                let _ = fs::remove_file(dir.join(format!("{}-wal", name)));
+                let _ = fs::remove_file(dir.join(format!("{}-shm", name)));
+            }
+            fs::rename(&partial, &dest).map_err(|e| format!("Failed to replace {}: {}", name, e))?;
+            restored.push(name.clone());
+        }
+        Ok(restored)
+    })();
+    
+    app_state::invalidate();
+    crate::modules::settings::settings::reload_all_from_settings();
+    if was_running {
+        log_reader::start_log_reader(app_handle)?;
+    }
+    result
+}
@@ -0,0 +1,2 @@
+// Backup module - zip backup/restore of the app data folder
+pub mod backup;
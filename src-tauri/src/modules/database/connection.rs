@@ -0,0 +1,65 @@
+// Connection: Shared data folder and SQLite open helpers
+//
+// data_dir is the one place the app data folder (LocalAppData\FCHClient) is resolved.
+// Every module keeps its own database file and schema, but opens it through app_db so
+// all of them wait on locks instead of failing with "database is locked" and use WAL.
+
+use lazy_static::lazy_static;
+use rusqlite::{Connection, Result as SqlResult};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long a connection waits for another writer before giving up
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+lazy_static! {
+    /// Database files already switched to WAL this run (journal_mode is persistent, so once is enough)
+    static ref WAL_CONFIGURED: Mutex<HashSet<PathBuf>> = Mutex::new(HashSet::new());
+}
+
+/// App data folder (LocalAppData\FCHClient on Windows) holding every database and JSON file
+pub fn data_dir() -> PathBuf {
+    let base = std::env::var("LOCALAPPDATA")
+        .ok()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("C:/Users/Public"));
+    base.join("FCHClient")
+}
+
+/// Open a database file in the data folder, creating the folder if needed.
+/// Callers run their own CREATE TABLE statements on the returned connection.
+pub fn app_db(file_name: &str) -> SqlResult<Connection> {
+    let dir = data_dir();
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        return Err(rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CANTOPEN),
+            Some(format!("Failed to create directory: {}", e))
+        ));
+    }
+    open_connection(&dir.join(file_name))
+}
+
+/// Open a database file with the shared busy timeout and WAL journal settings
+pub fn open_connection(path: &Path) -> SqlResult<Connection> {
+    let conn = Connection::open(path)?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    
+    let needs_wal = WAL_CONFIGURED
+        .lock()
+        .map(|configured| !configured.contains(path))
+        .unwrap_or(true);
+    if needs_wal {
+        let mode: String = conn.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))?;
+        if mode.eq_ignore_ascii_case("wal") {
+            if let Ok(mut configured) = WAL_CONFIGURED.lock() {
+                configured.insert(path.to_path_buf());
+            }
+        }
+    }
+    // synchronous is per connection; NORMAL is safe with WAL and avoids an fsync per commit
+    conn.execute_batch("PRAGMA synchronous = NORMAL")?;
+    
+    Ok(conn)
+}
@@ -0,0 +1,154 @@
+// Integrity: Startup health check and self-heal for the SQLite databases
+//
+// Each database file is checked with PRAGMA integrity_check and against the tables
+// and columns the owning module expects. A corrupt file can be moved aside
+// (timestamped) with recover_database, after which the owning module recreates a
+// fresh schema, so the app recovers instead of failing every query.
+
+use std::sync::Mutex;
+use rusqlite::{Connection, OpenFlags};
+use tauri::{AppHandle, Emitter};
+
+use super::connection;
+
+/// Tables (and their columns) each database file must contain
+const EXPECTED_SCHEMA: &[(&str, &str, &[&str])] = &[
+    ("world_mod_logs.db", "ban_logs", &["id", "admin", "target", "reason", "timestamp", "action_type", "location"]),
+    ("fchapp.db", "group_access", &["group_id", "group_name", "access_token"]),
+    ("fchapp.db", "watchlist_hits", &["id", "user_id", "username", "ts", "source"]),
+    ("fchapp.db", "user_firstseen", &["user_id", "username", "first_seen_ts"]),
+    ("fchapp.db", "session_labels", &["instance_ts", "label", "updated_at"]),
+    ("fchapp.db", "session_rosters", &["instance_ts", "location", "roster", "player_count", "ended_at"]),
+    ("fchapp.db", "instance_visits", &["instance_ts", "world_id", "instance_id", "room_name", "region"]),
+    ("fchapp.db", "favorite_worlds", &["world_id", "name", "added_at"]),
+    ("fchapp.db", "group_matches", &["user_id", "group_id", "group_name", "ts", "instance_ts"]),
+];
+
+/// Database file names known to this check
+fn known_files() -> Vec<&'static str> {
+    let mut files: Vec<&str> = EXPECTED_SCHEMA.iter().map(|(file, _, _)| *file).collect();
+    files.dedup();
+    files
+}
+
+/// Run a PRAGMA and collect one text column from every row
+fn query_strings(conn: &Connection, sql: &str, column: usize) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(column))?;
+    rows.collect()
+}
+
+/// Check a single database file; returns a list of problems (empty = healthy)
+fn check_file(file: &str) -> Vec<String> {
+    let path = connection::data_dir().join(file);
+    let conn = match Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_ONLY) {
+        Ok(c) => c,
+        Err(e) => return vec![format!("cannot open: {}", e)],
+    };
+    
+    let mut problems = Vec::new();
+    
+    match query_strings(&conn, "PRAGMA integrity_check", 0) {
+        Ok(rows) if rows.len() == 1 && rows[0] == "ok" => {}
+        Ok(rows) => problems.extend(rows.into_iter().map(|r| format!("integrity: {}", r))),
+        Err(e) => problems.push(format!("integrity check failed: {}", e)),
+    }
+    
+    for (_, table, columns) in EXPECTED_SCHEMA.iter().filter(|(f, _, _)| *f == file) {
+        match query_strings(&conn, &format!("PRAGMA table_info({})", table), 1) {
+            Ok(cols) if cols.is_empty() => problems.push(format!("missing table {}", table)),
+            Ok(cols) => {
+                for column in columns.iter().filter(|c| !cols.iter().any(|e| e == *c)) {
+                    problems.push(format!("missing column {}.{}", table, column));
+                }
+            }
+            Err(e) => problems.push(format!("cannot read table {}: {}", table, e)),
+        }
+    }
+    
+    problems
+}
+
+/// Result of the most recent check_database_integrity run (None until one finishes)
+static LAST_CHECK_HEALTHY: Mutex<Option<bool>> = Mutex::new(None);
+
+/// Whether the last integrity check passed (for the heartbeat; doesn't touch the databases)
+pub fn last_check_healthy() -> Option<bool> {
+    LAST_CHECK_HEALTHY.lock().ok().and_then(|last| *last)
+}
+
+/// Check every database. Returns { healthy, databases: [{ file, exists, healthy, problems }] }
+#[tauri::command]
+pub fn check_database_integrity() -> Result<serde_json::Value, String> {
+    let mut healthy = true;
+    let mut databases = Vec::new();
+    for file in known_files() {
+        // A missing file isn't a problem - the owning module creates it on init
+        let exists = connection::data_dir().join(file).exists();
+        let problems = if exists { check_file(file) } else { Vec::new() };
+        healthy &= problems.is_empty();
+        databases.push(serde_json::json!({
+            "file": file,
+            "exists": exists,
+            "healthy": problems.is_empty(),
+            "problems": problems
+        }));
+    }
+    if let Ok(mut last) = LAST_CHECK_HEALTHY.lock() {
+        *last = Some(healthy);
+    }
+    Ok(serde_json::json!({
+        "healthy": healthy,
+        "databases": databases
+    }))
+}
+
+/// Move a broken database aside (<file>.corrupt-<timestamp>) and recreate a fresh schema.
+/// Returns the path of the moved-aside copy.
+#[tauri::command]
+pub fn recover_database(file: String) -> Result<String, String> {
+    if !known_files().contains(&file.as_str()) {
+        return Err(format!("Unknown database: {}", file));
+    }
+    
+    let dir = connection::data_dir();
+    let path = dir.join(&file);
+    let backup = dir.join(format!("{}.corrupt-{}", file, chrono::Local::now().format("%Y%m%d-%H%M%S")));
+    if path.exists() {
+        std::fs::rename(&path, &backup)
+            .map_err(|e| format!("Failed to move {} aside: {}", file, e))?;
+    }
+    for suffix in ["-wal", "-shm", "-journal"] {
+        let _ = std::fs::remove_file(dir.join(format!("{}{}", file, suffix)));
+    }
+    
+    // Let the owning module recreate its schema
+    match file.as_str() {
+        "world_mod_logs.db" => crate::modules::world_mod::world_mod_logs::init_db()?,
+        "fchapp.db" => {
+            crate::modules::group_auth::group_access_tokens::init_db().map_err(|e| e.to_string())?;
+            crate::modules::watchlist::watchlist_hits::init_db()?;
+            crate::modules::history::first_seen::init_db()?;
+            crate::modules::history::session_labels::init_db()?;
+            crate::modules::history::session_rosters::init_db()?;
+            crate::modules::history::instance_visits::init_db()?;
+            crate::modules::history::favorite_worlds::init_db()?;
+            crate::modules::instance_monitor::group_matches::init_db()?;
+        }
+        _ => {}
+    }
+    
+    Ok(backup.to_string_lossy().to_string())
+}
+
+/// Run the check in the background at startup and emit database_unhealthy if anything is wrong
+pub fn check_on_startup(app_handle: AppHandle) {
+    std::thread::spawn(move || {
+        if let Ok(report) = check_database_integrity() {
+            if report["healthy"] != serde_json::Value::Bool(true) {
+                crate::debug_eprintln!("[database] Integrity check failed: {}", report);
+                let _ = app_handle.emit("database_unhealthy", report);
+            }
+        }
+    });
+}
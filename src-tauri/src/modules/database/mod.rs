@@ -0,0 +1,5 @@
+// Database module - shared SQLite maintenance (integrity checks, recovery)
+pub mod connection;
+pub mod integrity;
+pub mod stats;
+pub mod search;
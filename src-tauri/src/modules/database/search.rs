@@ -0,0 +1,68 @@
+// Search: One search box over every local store
+//
+// Read-only aggregation: notes.json (user ids, usernames, note text) and the
+// moderation log (admin/target). Results are grouped per category so the UI can
+// render them as sections.
+
+use std::collections::BTreeSet;
+
+use crate::modules::local_db::localdb;
+use crate::modules::world_mod::world_mod_logs;
+
+const DEFAULT_LIMIT_PER_CATEGORY: i64 = 20;
+
+/// Search users (notes/watchlist/usernames) and moderation logs for a name or id
+#[tauri::command]
+pub fn global_search(query: String, limit_per_category: Option<i64>) -> Result<serde_json::Value, String> {
+    let query = query.trim().to_string();
+    let limit = limit_per_category.unwrap_or(DEFAULT_LIMIT_PER_CATEGORY).clamp(1, 500) as usize;
+    if query.is_empty() {
+        return Ok(serde_json::json!({ "query": query, "users": [], "moderation": [] }));
+    }
+    let needle = query.to_lowercase();
+    
+    // Users: any id known to notes.json whose id, username or note text matches
+    let all = localdb::load_all_notes();
+    let user_ids: BTreeSet<&String> = all
+        .notes
+        .keys()
+        .chain(all.usernames.keys())
+        .chain(all.watchlist.keys())
+        .collect();
+    let users: Vec<serde_json::Value> = user_ids
+        .into_iter()
+        .filter_map(|user_id| {
+            let username = all.usernames.get(user_id);
+            let note = all.notes.get(user_id).and_then(|n| n.last());
+            let matched_on = if user_id.to_lowercase().contains(&needle) {
+                "userId"
+            } else if username.map_or(false, |u| u.to_lowercase().contains(&needle)) {
+                "username"
+            } else if note.map_or(false, |n| n.text.to_lowercase().contains(&needle)) {
+                "note"
+            } else {
+                return None;
+            };
+            Some(serde_json::json!({
+                "userId": user_id,
+                "username": username,
+                "note": note.map(|n| n.text.clone()),
+                "watch": all.watchlist.get(user_id).copied().unwrap_or(false),
+                "matchedOn": matched_on
+            }))
+        })
+        .take(limit)
+        .collect();
+    
+    // Moderation log: admin or target (already newest first)
+    let moderation: Vec<world_mod_logs::BanLogEntry> = world_mod_logs::search_ban_logs(&query)?
+        .into_iter()
+        .take(limit)
+        .collect();
+    
+    Ok(serde_json::json!({
+        "query": query,
+        "users": users,
+        "moderation": moderation
+    }))
+}
@@ -0,0 +1,47 @@
+// Stats: Row counts and on-disk sizes of everything the app stores
+//
+// Used by the database page to show what is taking space, and handy to paste
+// into bug reports.
+
+use super::connection;
+use crate::modules::group_auth::group_access_tokens;
+use crate::modules::local_db::localdb;
+use crate::modules::world_mod::world_mod_logs;
+
+/// Files in the data folder whose size is reported
+const DATA_FILES: &[&str] = &["notes.json", "settings.json", "app_state.json", "world_mod_logs.db", "fchapp.db"];
+
+/// Size of a file plus its SQLite WAL (0 when missing)
+fn file_size(name: &str) -> u64 {
+    let dir = connection::data_dir();
+    [name.to_string(), format!("{}-wal", name)]
+        .iter()
+        .filter_map(|n| std::fs::metadata(dir.join(n)).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Counts and file sizes for the database page
+#[tauri::command]
+pub fn get_database_stats() -> Result<serde_json::Value, String> {
+    let notes = localdb::load_all_notes();
+    let (bans, warns) = world_mod_logs::get_ban_log_counts()?;
+    let group_tokens = group_access_tokens::list_group_access_tokens()?.len();
+    
+    let files: serde_json::Map<String, serde_json::Value> = DATA_FILES
+        .iter()
+        .map(|name| (name.to_string(), serde_json::json!(file_size(name))))
+        .collect();
+    
+    Ok(serde_json::json!({
+        "notes": notes.notes.values().filter(|n| !n.is_empty()).count(),
+        "watchlist": notes.watchlist.values().filter(|w| **w).count(),
+        "knownUsernames": notes.usernames.len(),
+        "customSounds": notes.sounds.len(),
+        "banLogs": bans + warns,
+        "bans": bans,
+        "warns": warns,
+        "groupTokens": group_tokens,
+        "fileSizes": files
+    }))
+}
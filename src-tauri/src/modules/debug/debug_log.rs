@@ -1,19 +1,84 @@
 // Debug log helper - emits debug messages to frontend via Tauri events
 // Use this instead of println!/eprintln! to send logs to the debug panel
 
+use std::collections::VecDeque;
+use std::sync::Mutex;
 use tauri::AppHandle;
 use tauri::Emitter;
 
+/// How many recent messages are kept in memory for get_recent_debug
+const RECENT_DEBUG_MAX: usize = 1000;
+
+struct RecentDebugEntry {
+    ts: String,
+    level: String,
+    message: String,
+}
+
+// Ring buffer of recent messages so the debug panel can hydrate when it is opened
+static RECENT_DEBUG: Mutex<VecDeque<RecentDebugEntry>> = Mutex::new(VecDeque::new());
+
+fn push_recent(ts: &str, level: &str, message: &str) {
+    if let Ok(mut recent) = RECENT_DEBUG.lock() {
+        if recent.len() >= RECENT_DEBUG_MAX {
+            recent.pop_front();
+        }
+        recent.push_back(RecentDebugEntry {
+            ts: ts.to_string(),
+            level: level.to_string(),
+            message: message.to_string(),
+        });
+    }
+}
+
+/// Severity order for filtering: log < info < warn < error (unknown levels count as log)
+fn level_rank(level: &str) -> u8 {
+    match level {
+        "error" => 3,
+        "warn" => 2,
+        "info" => 1,
+        _ => 0,
+    }
+}
+
 /// Emit a debug log message to the frontend
+/// level is one of "log", "info", "warn", "error" (see the debug_* macros below)
 pub fn emit_debug_log(app_handle: &AppHandle, message: &str, level: &str) {
+    let ts = chrono::Utc::now().to_rfc3339();
+    push_recent(&ts, level, message);
+    super::log_file::append(&ts, level, message);
     let _ = app_handle.emit("debug_log", serde_json::json!({
         "message": message,
-        "ts": chrono::Utc::now().to_rfc3339(),
+        "ts": ts,
         "level": level,
         "source": "backend"
     }));
 }
 
+/// Get the most recent debug messages (oldest first), same shape as the debug_log event payload.
+/// min_level keeps only messages at or above that severity (e.g. "error" for errors only);
+/// limit applies after filtering.
+#[tauri::command]
+pub fn get_recent_debug(limit: Option<usize>, min_level: Option<String>) -> Result<Vec<serde_json::Value>, String> {
+    let recent = RECENT_DEBUG.lock().map_err(|e| e.to_string())?;
+    let min_rank = min_level.as_deref().map(level_rank).unwrap_or(0);
+    let matching: Vec<&RecentDebugEntry> = recent
+        .iter()
+        .filter(|e| level_rank(&e.level) >= min_rank)
+        .collect();
+    let limit = limit.unwrap_or(RECENT_DEBUG_MAX).min(matching.len());
+    Ok(matching
+        .iter()
+        .skip(matching.len() - limit)
+        .map(|e| serde_json::json!({
+            "message": e.message,
+            "ts": e.ts,
+            "level": e.level,
+            "source": "backend"
+        }))
+        .collect())
+}
+
 /// Convenience macros for different log levels
 #[macro_export]
 macro_rules! debug_log {
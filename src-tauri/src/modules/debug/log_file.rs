@@ -0,0 +1,101 @@
+// Debug log file sink - tees debug messages to a size-capped file on disk
+//
+// The debug panel only shows events while it is open, so this gives users a file
+// they can attach to bug reports after a crash. When the file reaches its size cap
+// it is renamed to "<path>.1" (replacing any older rotation) and a fresh file is started.
+// RotatingFile is shared with the events.jsonl export (log_reader::event_exporter).
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::modules::settings::settings;
+
+const DEFAULT_MAX_BYTES: u64 = 5 * 1024 * 1024; // 5MB
+const MIN_MAX_BYTES: u64 = 64 * 1024; // 64KB
+
+/// Line-oriented append-only file, renamed to "<path>.1" once it reaches max_bytes
+pub struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    file: Option<File>,
+    written: u64, // Current size of the active file
+}
+
+impl RotatingFile {
+    /// The file is opened lazily on the first write. max_bytes is raised to at least 64KB.
+    pub fn new(path: PathBuf, max_bytes: u64) -> Self {
+        let written = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Self { path, max_bytes: max_bytes.max(MIN_MAX_BYTES), file: None, written }
+    }
+
+    fn rotated_path(&self) -> PathBuf {
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(".1");
+        PathBuf::from(name)
+    }
+
+    /// Append one line (a newline is added), rotating first if it would exceed the cap
+    pub fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.written > 0 && self.written + len > self.max_bytes {
+            // Close the current file before renaming it (required on Windows)
+            self.file = None;
+            let _ = fs::remove_file(self.rotated_path());
+            fs::rename(&self.path, self.rotated_path())?;
+            self.written = 0;
+        }
+        if self.file.is_none() {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            self.file = Some(OpenOptions::new().create(true).append(true).open(&self.path)?);
+        }
+        if let Some(file) = self.file.as_mut() {
+            writeln!(file, "{}", line)?;
+            self.written += len;
+        }
+        Ok(())
+    }
+}
+
+static SINK: Mutex<Option<RotatingFile>> = Mutex::new(None);
+
+fn configure(path: Option<String>, max_bytes: Option<u64>) {
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_MAX_BYTES);
+    if let Ok(mut sink) = SINK.lock() {
+        *sink = path.map(|p| RotatingFile::new(PathBuf::from(p), max_bytes));
+    }
+}
+
+/// Append a debug message to the log file (no-op when no file is configured)
+pub fn append(ts: &str, level: &str, message: &str) {
+    if let Ok(mut guard) = SINK.lock() {
+        if let Some(sink) = guard.as_mut() {
+            if let Err(e) = sink.write_line(&format!("[{}] [{}] {}", ts, level, message)) {
+                crate::debug_eprintln!("[debug] Failed to write debug log file: {}", e);
+            }
+        }
+    }
+}
+
+/// Restore the file sink from settings (called once at startup)
+pub fn init_from_settings() -> Result<(), String> {
+    let settings = settings::get_settings()?;
+    configure(settings.debug_log_file, settings.debug_log_max_bytes);
+    Ok(())
+}
+
+/// Enable (path) or disable (None) writing debug messages to a file.
+/// max_bytes caps the file size before rotation (default 5MB).
+#[tauri::command]
+pub fn set_debug_log_file(path: Option<String>, max_bytes: Option<u64>) -> Result<(), String> {
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    settings::update_settings(|s| {
+        s.debug_log_file = path.clone();
+        s.debug_log_max_bytes = max_bytes;
+    })?;
+    configure(path, max_bytes);
+    Ok(())
+}
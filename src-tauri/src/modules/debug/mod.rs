@@ -1,2 +1,3 @@
 // Debug module - helper functions for emitting debug logs
 pub mod debug_log;
+pub mod log_file;
@@ -4,8 +4,8 @@
 // with the FCH backend API for group watchlist functionality.
 
 use rusqlite::{Connection, Result as SqlResult};
+use crate::modules::database::connection::app_db;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GroupAccessToken {
@@ -14,37 +14,9 @@ pub struct GroupAccessToken {
     pub access_token: String,
 }
 
-/// Get the directory where the database is stored
-/// Uses the same pathing as other modules (LocalAppData\FCHClient on Windows)
-fn db_dir() -> PathBuf {
-    let base = std::env::var("LOCALAPPDATA")
-        .ok()
-        .map(PathBuf::from)
-        .unwrap_or_else(|| PathBuf::from("C:/Users/Public"));
-    base.join("FCHClient")
-}
-
-/// Get the path to the SQLite database file
-/// Uses the same database as world_mod_logs for consistency
-fn db_path() -> PathBuf {
-    db_dir().join("fchapp.db")
-}
-
 /// Get or create database connection
 fn get_connection() -> SqlResult<Connection> {
-    let db_path = db_path();
-    
-    // Ensure directory exists
-    if let Some(parent) = db_path.parent() {
-        if let Err(e) = std::fs::create_dir_all(parent) {
-            return Err(rusqlite::Error::SqliteFailure(
-                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CANTOPEN),
-                Some(format!("Failed to create directory: {}", e))
-            ));
-        }
-    }
-    
-    let conn = Connection::open(&db_path)?;
+    let conn = app_db("fchapp.db")?;
     
     // Create table if it doesn't exist
     conn.execute(
@@ -0,0 +1,40 @@
+// Health: Heartbeat for a status footer and wedged-backend detection
+//
+// app_heartbeat only reads state other modules already keep (no disk or network
+// work beyond app_state), so the frontend can poll it every few seconds. If the
+// call stops returning, the backend is stuck.
+
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use crate::modules::database::integrity;
+use crate::modules::instance_monitor::batcher;
+use crate::modules::log_reader::log_reader;
+use crate::modules::updater::updater;
+
+/// When the app started (set once from run())
+static STARTED_AT: OnceLock<Instant> = OnceLock::new();
+
+/// Remember the start time for uptime (called at the top of run())
+pub fn mark_started() {
+    let _ = STARTED_AT.set(Instant::now());
+}
+
+/// Seconds since mark_started (0 if it was never called)
+fn uptime_secs() -> u64 {
+    STARTED_AT.get().map(|t| t.elapsed().as_secs()).unwrap_or(0)
+}
+
+/// { version, uptimeSecs, watcherRunning, dbOk, pendingApiJobs, pendingGroupChecks }
+/// dbOk is the result of the most recent integrity check (null until one has finished).
+#[tauri::command]
+pub fn app_heartbeat() -> Result<serde_json::Value, String> {
+    Ok(serde_json::json!({
+        "version": updater::app_version(),
+        "uptimeSecs": uptime_secs(),
+        "watcherRunning": log_reader::is_log_reader_running(),
+        "dbOk": integrity::last_check_healthy(),
+        "pendingApiJobs": batcher::in_flight_batches(),
+        "pendingGroupChecks": batcher::pending_count()
+    }))
+}
@@ -0,0 +1,2 @@
+// Health module - cheap status snapshot the frontend can poll
+pub mod health;
@@ -0,0 +1,127 @@
+// Favorite Worlds: Worlds the user starred, with a link to their VRChat page
+//
+// A small personal list kept next to the instance visits. The name is whatever the
+// user (or the visited-worlds page) passed in; when it is empty we fall back to the
+// latest room name recorded for that world.
+
+use rusqlite::{Connection, Result as SqlResult};
+use crate::modules::database::connection::app_db;
+use crate::modules::history::instance_visits;
+
+/// Get or create database connection
+fn get_connection() -> SqlResult<Connection> {
+    let conn = app_db("fchapp.db")?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS favorite_worlds (
+            world_id TEXT PRIMARY KEY,
+            name TEXT,
+            added_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    Ok(conn)
+}
+
+/// Initialize the table
+pub fn init_db() -> Result<(), String> {
+    get_connection().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Trim a world id and check it looks like one (wrld_ followed by a uuid)
+fn normalize_world_id(world_id: &str) -> Result<String, String> {
+    let world_id = world_id.trim();
+    let valid = world_id
+        .strip_prefix("wrld_")
+        .is_some_and(|rest| !rest.is_empty() && rest.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+    if !valid {
+        return Err(format!("Not a world id: {}", world_id));
+    }
+    Ok(world_id.to_string())
+}
+
+/// Star a world (updates the name if it is already a favorite)
+#[tauri::command]
+pub fn add_favorite_world(world_id: String, name: Option<String>) -> Result<(), String> {
+    let world_id = normalize_world_id(&world_id)?;
+    let name = name.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO favorite_worlds (world_id, name, added_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(world_id) DO UPDATE SET name = COALESCE(excluded.name, favorite_worlds.name)",
+        rusqlite::params![world_id, name, now],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Unstar a world. Returns false if it wasn't a favorite.
+#[tauri::command]
+pub fn remove_favorite_world(world_id: String) -> Result<bool, String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    let removed = conn
+        .execute("DELETE FROM favorite_worlds WHERE world_id = ?1", [world_id.trim()])
+        .map_err(|e| e.to_string())?;
+    Ok(removed > 0)
+}
+
+/// List favorite worlds, most recently starred first, with how often and when we last visited them.
+/// Returns [{ worldId, name, addedAt, visits, lastVisited }].
+#[tauri::command]
+pub fn list_favorite_worlds() -> Result<Vec<serde_json::Value>, String> {
+    // Visit counts come from instance_visits; make sure it exists before joining
+    instance_visits::init_db()?;
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT f.world_id,
+                    COALESCE(f.name, (SELECT n.room_name FROM instance_visits n
+                                      WHERE n.world_id = f.world_id AND n.room_name IS NOT NULL
+                                      ORDER BY n.instance_ts DESC LIMIT 1)),
+                    f.added_at,
+                    COUNT(v.instance_ts),
+                    MAX(v.instance_ts)
+             FROM favorite_worlds f
+             LEFT JOIN instance_visits v ON v.world_id = f.world_id
+             GROUP BY f.world_id
+             ORDER BY f.added_at DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(serde_json::json!({
+                "worldId": row.get::<_, String>(0)?,
+                "name": row.get::<_, Option<String>>(1)?,
+                "addedAt": row.get::<_, String>(2)?,
+                "visits": row.get::<_, i64>(3)?,
+                "lastVisited": row.get::<_, Option<String>>(4)?,
+            }))
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<SqlResult<Vec<_>>>().map_err(|e| e.to_string())
+}
+
+/// Open a world's VRChat page in the default browser. Returns the URL opened.
+#[tauri::command]
+pub fn open_world_page(world_id: String) -> Result<String, String> {
+    let world_id = normalize_world_id(&world_id)?;
+    let url = format!("https://vrchat.com/home/world/{}", world_id);
+
+    #[cfg(target_os = "windows")]
+    let result = std::process::Command::new("cmd")
+        .args(["/C", "start", "", &url])
+        .spawn();
+
+    #[cfg(target_os = "macos")]
+    let result = std::process::Command::new("open").arg(&url).spawn();
+
+    #[cfg(target_os = "linux")]
+    let result = std::process::Command::new("xdg-open").arg(&url).spawn();
+
+    result
+        .map(|_| url.clone())
+        .map_err(|e| format!("Failed to open {}: {}", url, e))
+}
@@ -0,0 +1,71 @@
+// First Seen: When each player was first seen joining
+//
+// One row per user id with the log timestamp of the very first join we parsed.
+// A join is "first seen" when its timestamp is that first sighting, so the flag
+// stays stable when the same log is re-scanned (backfill / manual refresh).
+
+use rusqlite::{Connection, OptionalExtension, Result as SqlResult};
+use crate::modules::database::connection::app_db;
+
+/// Get or create database connection
+fn get_connection() -> SqlResult<Connection> {
+    let conn = app_db("fchapp.db")?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS user_firstseen (
+            user_id TEXT PRIMARY KEY,
+            username TEXT,
+            first_seen_ts TEXT NOT NULL
+        )",
+        [],
+    )?;
+    
+    Ok(conn)
+}
+
+/// Initialize the table
+pub fn init_db() -> Result<(), String> {
+    get_connection().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Record a join and return true if it is the user's first sighting.
+/// timestamp: log timestamp of the join line (YYYY.MM.DD HH:MM:SS)
+pub fn record_join(user_id: &str, username: &str, timestamp: &str) -> Result<bool, String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR IGNORE INTO user_firstseen (user_id, username, first_seen_ts) VALUES (?1, ?2, ?3)",
+        rusqlite::params![user_id, username, timestamp],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    // A join parsed out of order (older than the stored one) becomes the first sighting
+    conn.execute(
+        "UPDATE user_firstseen SET first_seen_ts = ?2 WHERE user_id = ?1 AND first_seen_ts > ?2",
+        rusqlite::params![user_id, timestamp],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    let first: Option<String> = conn
+        .query_row(
+            "SELECT first_seen_ts FROM user_firstseen WHERE user_id = ?1",
+            [user_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    Ok(first.as_deref() == Some(timestamp))
+}
+
+/// When a user was first seen (None if never)
+#[tauri::command]
+pub fn get_first_seen(user_id: String) -> Result<Option<String>, String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    conn.query_row(
+        "SELECT first_seen_ts FROM user_firstseen WHERE user_id = ?1",
+        [user_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
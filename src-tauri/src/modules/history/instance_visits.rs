@@ -0,0 +1,169 @@
+// Instance Visits: One row per instance we joined
+//
+// Recorded by the log parser from each "Joining wrld_..." line (keyed by that line's
+// timestamp, like session labels), with the room name filled in when the following
+// "Joining or Creating Room" line arrives. Backfill replays hit the same key, so a
+// visit is only stored once.
+
+use rusqlite::{Connection, Result as SqlResult};
+use crate::modules::database::connection::app_db;
+
+/// Get or create database connection
+fn get_connection() -> SqlResult<Connection> {
+    let conn = app_db("fchapp.db")?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS instance_visits (
+            instance_ts TEXT PRIMARY KEY,
+            world_id TEXT NOT NULL,
+            instance_id TEXT,
+            room_name TEXT,
+            region TEXT,
+            instance_type TEXT,
+            group_id TEXT
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_instance_visits_world ON instance_visits(world_id)",
+        [],
+    )?;
+    
+    Ok(conn)
+}
+
+/// Initialize the table
+pub fn init_db() -> Result<(), String> {
+    get_connection().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Store an instance join (ignored if this instance_ts was already recorded)
+pub fn record_visit(
+    instance_ts: &str,
+    world_id: &str,
+    instance_id: Option<&str>,
+    region: Option<&str>,
+    instance_type: Option<&str>,
+    group_id: Option<&str>,
+) -> Result<(), String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR IGNORE INTO instance_visits (instance_ts, world_id, instance_id, region, instance_type, group_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![instance_ts, world_id, instance_id, region, instance_type, group_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Attach the room (world) name to a recorded visit
+pub fn set_room_name(instance_ts: &str, room_name: &str) -> Result<(), String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE instance_visits SET room_name = ?1 WHERE instance_ts = ?2",
+        rusqlite::params![room_name, instance_ts],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Instances joined per region between from_ts and to_ts (log timestamps, inclusive, either optional).
+/// Instances without a ~region tag (VRChat picked the region) are counted under "auto".
+/// Returns [{ region, visits, worlds }], most visited first.
+#[tauri::command]
+pub fn get_region_stats(from_ts: Option<String>, to_ts: Option<String>) -> Result<Vec<serde_json::Value>, String> {
+    let from_ts = from_ts.filter(|s| !s.trim().is_empty());
+    let to_ts = to_ts.filter(|s| !s.trim().is_empty());
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(NULLIF(region, ''), 'auto') AS bucket,
+                    COUNT(*) AS visits,
+                    COUNT(DISTINCT world_id) AS worlds
+             FROM instance_visits
+             WHERE (?1 IS NULL OR instance_ts >= ?1) AND (?2 IS NULL OR instance_ts <= ?2)
+             GROUP BY bucket
+             ORDER BY visits DESC, bucket ASC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![from_ts, to_ts], |row| {
+            Ok(serde_json::json!({
+                "region": row.get::<_, String>(0)?,
+                "visits": row.get::<_, i64>(1)?,
+                "worlds": row.get::<_, i64>(2)?,
+            }))
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<SqlResult<Vec<_>>>().map_err(|e| e.to_string())
+}
+
+/// Distinct worlds joined between from_ts and to_ts (log timestamps, inclusive, either optional),
+/// most recently visited first. search matches the world id or name (case-insensitive).
+/// There is no world-name cache, so name is the latest room name seen for that world.
+/// Returns { total, items: [{ worldId, name, visits, lastVisited }] }; limit defaults to 50 (max 500).
+#[tauri::command]
+pub fn list_visited_worlds(
+    from_ts: Option<String>,
+    to_ts: Option<String>,
+    search: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<serde_json::Value, String> {
+    let from_ts = from_ts.filter(|s| !s.trim().is_empty());
+    let to_ts = to_ts.filter(|s| !s.trim().is_empty());
+    let search_pattern = search
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(|s| format!("%{}%", s));
+    let limit = limit.unwrap_or(50).clamp(1, 500);
+    let offset = offset.unwrap_or(0);
+    
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    
+    // One row per world in range; shared by the count and the page query
+    let worlds = "WITH worlds AS (
+            SELECT v.world_id,
+                   COUNT(*) AS visits,
+                   MAX(v.instance_ts) AS last_visited,
+                   (SELECT n.room_name FROM instance_visits n
+                    WHERE n.world_id = v.world_id AND n.room_name IS NOT NULL
+                    ORDER BY n.instance_ts DESC LIMIT 1) AS name
+            FROM instance_visits v
+            WHERE (?1 IS NULL OR v.instance_ts >= ?1) AND (?2 IS NULL OR v.instance_ts <= ?2)
+            GROUP BY v.world_id
+        )";
+    let filter = "WHERE ?3 IS NULL OR world_id LIKE ?3 OR name LIKE ?3";
+    
+    let total: i64 = conn
+        .query_row(
+            &format!("{} SELECT COUNT(*) FROM worlds {}", worlds, filter),
+            rusqlite::params![from_ts, to_ts, search_pattern],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let mut stmt = conn
+        .prepare(&format!(
+            "{} SELECT world_id, name, visits, last_visited FROM worlds {}
+             ORDER BY last_visited DESC LIMIT ?4 OFFSET ?5",
+            worlds, filter
+        ))
+        .map_err(|e| e.to_string())?;
+    let items = stmt
+        .query_map(rusqlite::params![from_ts, to_ts, search_pattern, limit, offset], |row| {
+            Ok(serde_json::json!({
+                "worldId": row.get::<_, String>(0)?,
+                "name": row.get::<_, Option<String>>(1)?,
+                "visits": row.get::<_, i64>(2)?,
+                "lastVisited": row.get::<_, String>(3)?,
+            }))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<SqlResult<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+    
+    Ok(serde_json::json!({ "total": total, "items": items }))
+}
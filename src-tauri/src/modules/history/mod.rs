@@ -0,0 +1,6 @@
+// History module - long-lived facts about players and instances seen in the logs
+pub mod first_seen;
+pub mod session_labels;
+pub mod session_rosters;
+pub mod instance_visits;
+pub mod favorite_worlds;
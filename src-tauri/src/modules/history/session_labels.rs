@@ -0,0 +1,90 @@
+// Session Labels: User bookmarks on instance sessions
+//
+// Keyed by the timestamp of the instance join entry in the instance history,
+// so get_instance_history can show the label next to the session.
+
+use rusqlite::{Connection, Result as SqlResult};
+use crate::modules::database::connection::app_db;
+use std::collections::HashMap;
+
+/// Get or create database connection
+fn get_connection() -> SqlResult<Connection> {
+    let conn = app_db("fchapp.db")?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS session_labels (
+            instance_ts TEXT PRIMARY KEY,
+            label TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    
+    Ok(conn)
+}
+
+/// Initialize the table
+pub fn init_db() -> Result<(), String> {
+    get_connection().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// All labels keyed by instance timestamp
+pub fn load_labels() -> Result<HashMap<String, String>, String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT instance_ts, label FROM session_labels")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| e.to_string())?;
+    let mut labels = HashMap::new();
+    for row in rows {
+        let (ts, label) = row.map_err(|e| e.to_string())?;
+        labels.insert(ts, label);
+    }
+    Ok(labels)
+}
+
+/// Set (or clear, with an empty label) the label for an instance session
+#[tauri::command]
+pub fn set_session_label(instance_ts: String, label: String) -> Result<(), String> {
+    let instance_ts = instance_ts.trim();
+    if instance_ts.is_empty() {
+        return Err("instance_ts is required".to_string());
+    }
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    let label = label.trim();
+    if label.is_empty() {
+        conn.execute("DELETE FROM session_labels WHERE instance_ts = ?1", [instance_ts])
+            .map_err(|e| e.to_string())?;
+    } else {
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        conn.execute(
+            "INSERT INTO session_labels (instance_ts, label, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(instance_ts) DO UPDATE SET label = excluded.label, updated_at = excluded.updated_at",
+            rusqlite::params![instance_ts, label, now],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// List all session labels, newest session first
+#[tauri::command]
+pub fn list_session_labels() -> Result<Vec<serde_json::Value>, String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT instance_ts, label, updated_at FROM session_labels ORDER BY instance_ts DESC")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(serde_json::json!({
+                "instanceTs": row.get::<_, String>(0)?,
+                "label": row.get::<_, String>(1)?,
+                "updatedAt": row.get::<_, String>(2)?,
+            }))
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<SqlResult<Vec<_>>>().map_err(|e| e.to_string())
+}
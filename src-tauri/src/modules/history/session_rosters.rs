@@ -0,0 +1,97 @@
+// Session Rosters: Who was still in an instance when we left it
+//
+// Snapshotted by the log parser right before it clears the live roster on an
+// instance change, keyed by the same instance timestamp as session labels.
+
+use rusqlite::{Connection, OptionalExtension, Result as SqlResult};
+use crate::modules::database::connection::app_db;
+
+/// Get or create database connection
+fn get_connection() -> SqlResult<Connection> {
+    let conn = app_db("fchapp.db")?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS session_rosters (
+            instance_ts TEXT PRIMARY KEY,
+            location TEXT NOT NULL,
+            room_name TEXT,
+            roster TEXT NOT NULL,
+            player_count INTEGER NOT NULL,
+            ended_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    
+    Ok(conn)
+}
+
+/// Initialize the table
+pub fn init_db() -> Result<(), String> {
+    get_connection().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Store the roster of the instance being left (replaces an earlier snapshot of the same session)
+pub fn record_roster(instance_ts: &str, location: &str, room_name: Option<&str>, roster: &[serde_json::Value], ended_at: &str) -> Result<(), String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO session_rosters (instance_ts, location, room_name, roster, player_count, ended_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            instance_ts,
+            location,
+            room_name,
+            serde_json::Value::from(roster.to_vec()).to_string(),
+            roster.len() as i64,
+            ended_at
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn row_to_json(row: &rusqlite::Row, with_roster: bool) -> SqlResult<serde_json::Value> {
+    let mut value = serde_json::json!({
+        "instanceTs": row.get::<_, String>(0)?,
+        "location": row.get::<_, String>(1)?,
+        "roomName": row.get::<_, Option<String>>(2)?,
+        "playerCount": row.get::<_, i64>(4)?,
+        "endedAt": row.get::<_, String>(5)?,
+    });
+    if with_roster {
+        let roster: String = row.get(3)?;
+        value["roster"] = serde_json::from_str(&roster).unwrap_or_else(|_| serde_json::json!([]));
+    }
+    Ok(value)
+}
+
+/// Full roster snapshot for one session, or None if nothing was recorded
+#[tauri::command]
+pub fn get_session_roster(instance_ts: String) -> Result<Option<serde_json::Value>, String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    conn.query_row(
+        "SELECT instance_ts, location, room_name, roster, player_count, ended_at
+         FROM session_rosters WHERE instance_ts = ?1",
+        [instance_ts.trim()],
+        |row| row_to_json(row, true),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Recent session snapshots without the player lists, newest session first
+#[tauri::command]
+pub fn list_session_rosters(limit: Option<u32>) -> Result<Vec<serde_json::Value>, String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    let limit = limit.unwrap_or(50).clamp(1, 1000);
+    let mut stmt = conn
+        .prepare(
+            "SELECT instance_ts, location, room_name, roster, player_count, ended_at
+             FROM session_rosters ORDER BY instance_ts DESC LIMIT ?1",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([limit], |row| row_to_json(row, false))
+        .map_err(|e| e.to_string())?;
+    rows.collect::<SqlResult<Vec<_>>>().map_err(|e| e.to_string())
+}
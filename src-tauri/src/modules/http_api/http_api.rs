@@ -0,0 +1,298 @@
+// HTTP API: Optional local read-only API for scripting the app
+//
+// Off by default. When enabled (http_api_enabled in settings) a small HTTP server is
+// bound to 127.0.0.1 only, and every request must carry the generated token in an
+// X-FCH-Token header. Endpoints mirror existing commands:
+//   GET /status            watcher state, current location, authenticated user
+//   GET /active-users      other players currently in the instance
+//   GET /instance-history  recent instance joins/leaves
+//   GET /moderation?limit= most recent ban/warn entries (default 50)
+//   GET /events?limit=     most recent parsed log events, oldest first (default 100,
+//                          same schema as events.jsonl, see event_exporter)
+
+use lazy_static::lazy_static;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::modules::app_state::app_state;
+use crate::modules::log_reader::{event_exporter, log_parser, log_reader};
+use crate::modules::settings::settings;
+use crate::modules::world_mod::world_mod_logs;
+
+const DEFAULT_PORT: u16 = 17420;
+const TOKEN_KEY: &str = "http_api_token";
+const TOKEN_HEADER: &str = "x-fch-token";
+/// Largest request head we accept (we never read bodies)
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+/// Longest a single connection may hold the accept thread
+const REQUEST_DEADLINE: Duration = Duration::from_secs(2);
+
+struct RunningServer {
+    port: u16,
+    stop: Arc<AtomicBool>,
+    thread: std::thread::JoinHandle<()>,
+}
+
+lazy_static! {
+    static ref SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+}
+
+/// Random 64 hex character token from the OS CSPRNG
+fn generate_token() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("Failed to generate API token: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Stored token, generating one on first use
+fn api_token() -> Result<String, String> {
+    if let Some(token) = app_state::get(TOKEN_KEY).filter(|t| !t.is_empty()) {
+        return Ok(token);
+    }
+    let token = generate_token()?;
+    app_state::set(TOKEN_KEY, Some(&token))?;
+    Ok(token)
+}
+
+fn start_server(port: u16) -> Result<(), String> {
+    stop_server();
+    
+    let token = api_token()?;
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .map_err(|e| format!("Failed to bind 127.0.0.1:{}: {}", port, e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure listener: {}", e))?;
+    
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_flag = stop.clone();
+    let thread = std::thread::Builder::new()
+        .name("http-api".to_string())
+        .spawn(move || {
+            while !stop_flag.load(Ordering::SeqCst) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        // Handled inline: requests are tiny and bounded by REQUEST_DEADLINE,
+                        // so one slow client can't pile up threads
+                        if let Err(e) = handle_connection(stream, &token) {
+                            crate::debug_eprintln!("[http_api] Request failed: {}", e);
+                        }
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                        std::thread::sleep(Duration::from_millis(100));
+                    }
+                    Err(e) => {
+                        crate::debug_eprintln!("[http_api] Accept failed: {}", e);
+                        std::thread::sleep(Duration::from_millis(100));
+                    }
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to start HTTP API thread: {}", e))?;
+    
+    if let Ok(mut server) = SERVER.lock() {
+        *server = Some(RunningServer { port, stop, thread });
+    }
+    crate::debug_println!("[http_api] Listening on 127.0.0.1:{}", port);
+    Ok(())
+}
+
+/// Stop the server and wait for the accept loop to release the port
+fn stop_server() {
+    let running = SERVER.lock().ok().and_then(|mut server| server.take());
+    if let Some(running) = running {
+        running.stop.store(true, Ordering::SeqCst);
+        let _ = running.thread.join();
+    }
+}
+
+/// Compare two byte strings without exiting early on the first mismatch
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn write_response(stream: &mut TcpStream, status: &str, body: &serde_json::Value) -> std::io::Result<()> {
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+fn handle_connection(mut stream: TcpStream, token: &str) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_write_timeout(Some(REQUEST_DEADLINE))?;
+    
+    // Read the request head only; each read gets what's left of the deadline so a
+    // client trickling bytes can't hold the accept thread past it
+    let started = Instant::now();
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        let remaining = REQUEST_DEADLINE
+            .checked_sub(started.elapsed())
+            .filter(|d| !d.is_zero())
+            .ok_or_else(|| std::io::Error::new(ErrorKind::TimedOut, "request head not received in time"))?;
+        stream.set_read_timeout(Some(remaining))?;
+        let n = stream.read(&mut chunk)?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if buf.len() > MAX_REQUEST_BYTES {
+            return write_response(&mut stream, "431 Request Header Fields Too Large", &serde_json::json!({ "error": "request too large" }));
+        }
+    }
+    
+    let head = String::from_utf8_lossy(&buf);
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or("").split_whitespace();
+    let method = request_line.next().unwrap_or("");
+    let target = request_line.next().unwrap_or("");
+    let presented = lines
+        .filter_map(|l| l.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case(TOKEN_HEADER))
+        .map(|(_, value)| value.trim().to_string());
+    
+    if !presented.is_some_and(|p| constant_time_eq(p.as_bytes(), token.as_bytes())) {
+        return write_response(&mut stream, "401 Unauthorized", &serde_json::json!({ "error": "missing or invalid X-FCH-Token" }));
+    }
+    if method != "GET" {
+        return write_response(&mut stream, "405 Method Not Allowed", &serde_json::json!({ "error": "read-only API, use GET" }));
+    }
+    
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let result = match path {
+        "/status" => Ok(serde_json::json!({
+            "watcherRunning": log_reader::is_log_reader_running(),
+            "location": log_parser::get_current_location().unwrap_or(serde_json::Value::Null),
+            "instanceJoinedAt": log_parser::get_current_instance_timestamp(),
+            "selfUser": log_parser::get_authenticated_user().unwrap_or(serde_json::Value::Null)
+        })),
+        "/active-users" => Ok(serde_json::Value::Array(log_parser::get_active_players())),
+        "/instance-history" => log_parser::get_instance_history().map(serde_json::Value::Array),
+        "/moderation" => {
+            let limit = query_limit(query, 50, 1000);
+            world_mod_logs::get_all_ban_logs()
+                .map(|mut entries| {
+                    entries.truncate(limit);
+                    entries
+                })
+                .and_then(|entries| serde_json::to_value(entries).map_err(|e| e.to_string()))
+        }
+        "/events" => Ok(serde_json::Value::Array(event_exporter::recent_events(query_limit(query, 100, 500)))),
+        _ => return write_response(&mut stream, "404 Not Found", &serde_json::json!({ "error": "unknown endpoint" })),
+    };
+    
+    match result {
+        Ok(body) => write_response(&mut stream, "200 OK", &body),
+        Err(e) => write_response(&mut stream, "500 Internal Server Error", &serde_json::json!({ "error": e })),
+    }
+}
+
+/// Read ?limit= from a query string, falling back to the default and capped at max
+fn query_limit(query: &str, default: usize, max: usize) -> usize {
+    query
+        .split('&')
+        .filter_map(|p| p.split_once('='))
+        .find(|(k, _)| *k == "limit")
+        .and_then(|(_, v)| v.parse::<usize>().ok())
+        .unwrap_or(default)
+        .min(max)
+}
+
+fn status_json() -> Result<serde_json::Value, String> {
+    let s = settings::get_settings()?;
+    let running_port = SERVER.lock().ok().and_then(|server| server.as_ref().map(|r| r.port));
+    Ok(serde_json::json!({
+        "enabled": s.http_api_enabled,
+        "running": running_port.is_some(),
+        "port": running_port.unwrap_or(s.http_api_port.unwrap_or(DEFAULT_PORT)),
+        "token": api_token()?
+    }))
+}
+
+/// Start (or restart) the API when enabled in settings, stop it otherwise
+/// (startup and after a backup restore; a restart also picks up a restored token)
+pub fn load_config() {
+    let Ok(s) = settings::get_settings() else {
+        return;
+    };
+    if s.http_api_enabled {
+        if let Err(e) = start_server(s.http_api_port.unwrap_or(DEFAULT_PORT)) {
+            crate::debug_eprintln!("[http_api] {}", e);
+        }
+    } else {
+        stop_server();
+    }
+}
+
+/// Enable/disable the local API and optionally change its port (None = keep / default 17420).
+/// Returns { enabled, running, port, token }.
+#[tauri::command]
+pub fn set_http_api(enabled: bool, port: Option<u16>) -> Result<serde_json::Value, String> {
+    let port = port.filter(|p| *p != 0);
+    settings::update_settings(|s| {
+        s.http_api_enabled = enabled;
+        if port.is_some() {
+            s.http_api_port = port;
+        }
+    })?;
+    if enabled {
+        let effective = port.or(settings::get_settings()?.http_api_port).unwrap_or(DEFAULT_PORT);
+        start_server(effective)?;
+    } else {
+        stop_server();
+    }
+    status_json()
+}
+
+/// Current API state including the token clients must send
+#[tauri::command]
+pub fn get_http_api_status() -> Result<serde_json::Value, String> {
+    status_json()
+}
+
+/// Replace the token (old clients stop working); restarts the server if running
+#[tauri::command]
+pub fn regenerate_http_api_token() -> Result<serde_json::Value, String> {
+    app_state::set(TOKEN_KEY, Some(&generate_token()?))?;
+    let running_port = SERVER.lock().ok().and_then(|server| server.as_ref().map(|r| r.port));
+    if let Some(port) = running_port {
+        start_server(port)?;
+    }
+    status_json()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_tokens_are_64_hex_chars_and_differ() {
+        let a = generate_token().unwrap();
+        let b = generate_token().unwrap();
+        assert_eq!(a.len(), 64);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn constant_time_eq_matches_only_identical_input() {
+        assert!(constant_time_eq(b"abc123", b"abc123"));
+        assert!(!constant_time_eq(b"abc123", b"abc124"));
+        assert!(!constant_time_eq(b"abc123", b"abc12"));
+        assert!(!constant_time_eq(b"", b"a"));
+        assert!(constant_time_eq(b"", b""));
+    }
+}
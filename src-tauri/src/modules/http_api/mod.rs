@@ -0,0 +1,2 @@
+// HTTP API module - optional local read-only API for automation tools
+pub mod http_api;
@@ -0,0 +1,35 @@
+// HTTP Client: One shared reqwest::Client for the whole app
+//
+// A Client owns a connection pool, so building one per request meant a fresh TLS
+// handshake for every moderation-log post and group check, and with no timeouts a
+// stalled server could hang a request forever. Everything now goes through client().
+//
+// The client only sets a connect timeout; API calls add API_TIMEOUT per request so
+// large downloads (updater) aren't cut off by a total-time limit.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Time allowed to establish a connection (DNS + TCP + TLS)
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Idle pooled connections are closed after this long
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+/// Total time for a small JSON API request (use with RequestBuilder::timeout)
+pub const API_TIMEOUT: Duration = Duration::from_secs(30);
+
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+/// Shared client (created on first use)
+pub fn client() -> &'static reqwest::Client {
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+            .user_agent(concat!("FCH-Toolkit-App/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_else(|e| {
+                crate::debug_eprintln!("[http_client] Failed to build client, using defaults: {}", e);
+                reqwest::Client::new()
+            })
+    })
+}
@@ -0,0 +1,2 @@
+// HTTP Client module - shared reqwest client for backend and updater requests
+pub mod http_client;
@@ -0,0 +1,122 @@
+// Image Cache: Downloaded images kept under FCHClient/image_cache
+//
+// cache_image returns a local file path for a remote image, downloading it once via the
+// shared HTTP client. Files are named by a hash of the URL, so a cached image is served
+// without any network access (offline review, no re-download on every render).
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+use crate::modules::http_client::http_client;
+use crate::modules::database::connection;
+
+/// Largest image we'll store
+const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Get the directory where cached images are stored
+fn cache_dir() -> PathBuf {
+    connection::data_dir().join("image_cache")
+}
+
+/// File stem for a URL (DefaultHasher is deterministic; a Rust upgrade that changes it
+/// only means images are downloaded once more)
+fn url_key(url: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// File extension for an image content type (None when it isn't an image we keep)
+fn extension_for(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    match mime.as_str() {
+        "image/png" => Some("png"),
+        "image/jpeg" | "image/jpg" => Some("jpg"),
+        "image/gif" => Some("gif"),
+        "image/webp" => Some("webp"),
+        "image/bmp" => Some("bmp"),
+        _ => None,
+    }
+}
+
+/// Already cached file for this key, whatever its extension
+fn find_cached(key: &str) -> Option<PathBuf> {
+    fs::read_dir(cache_dir())
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .find(|p| p.file_stem().map_or(false, |s| s == key) && p.extension().map_or(false, |e| e != "part"))
+}
+
+/// Return a local path for a remote image, downloading it on first use
+#[tauri::command]
+pub async fn cache_image(url: String) -> Result<String, String> {
+    let url = url.trim().to_string();
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(format!("Not an http(s) URL: {}", url));
+    }
+    
+    let key = url_key(&url);
+    if let Some(path) = find_cached(&key) {
+        return Ok(path.to_string_lossy().to_string());
+    }
+    
+    let response = http_client::client()
+        .get(&url)
+        .timeout(http_client::API_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download image: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Image download failed with status: {}", response.status()));
+    }
+    
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let ext = extension_for(&content_type)
+        .ok_or_else(|| format!("Not an image (content type '{}')", content_type))?;
+    if response.content_length().map_or(false, |len| len as usize > MAX_IMAGE_BYTES) {
+        return Err("Image is too large to cache".to_string());
+    }
+    
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return Err("Image is too large to cache".to_string());
+    }
+    
+    // Write to a temp name and rename so a half-written file is never served
+    let dir = cache_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create image cache folder: {}", e))?;
+    let path = dir.join(format!("{}.{}", key, ext));
+    let partial = dir.join(format!("{}.part", key));
+    fs::write(&partial, &bytes).map_err(|e| format!("Failed to save image: {}", e))?;
+    fs::rename(&partial, &path).map_err(|e| format!("Failed to save image: {}", e))?;
+    
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Delete every cached image. Returns { files, bytes } removed.
+#[tauri::command]
+pub fn clear_image_cache() -> Result<serde_json::Value, String> {
+    let mut files = 0u64;
+    let mut bytes = 0u64;
+    if let Ok(entries) = fs::read_dir(cache_dir()) {
+        for entry in entries.flatten() {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            if entry.path().is_file() && fs::remove_file(entry.path()).is_ok() {
+                files += 1;
+                bytes += size;
+            }
+        }
+    }
+    Ok(serde_json::json!({ "files": files, "bytes": bytes }))
+}
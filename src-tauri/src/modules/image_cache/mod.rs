@@ -0,0 +1,2 @@
+// Image Cache module - local copies of remote images (profile/world thumbnails)
+pub mod image_cache;
@@ -1,10 +1,13 @@
 // Group Watchlist Batcher: Batches user IDs and sends them to check-user endpoint
 //
-// This module listens to player_joined events, batches user IDs for 1 second,
-// then sends them to the backend /check-user endpoint with all stored tokens.
+// This module listens to player_joined events, batches user IDs until joins have been
+// quiet for the debounce window (1 second by default), then sends them to the backend
+// /check-user endpoint with all stored tokens. A batch that reaches the max size is sent
+// immediately so a fast-filling instance doesn't wait for the joins to stop.
 
 use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, async_runtime};
 use serde::{Deserialize, Serialize};
@@ -35,6 +38,11 @@ pub struct CheckUserResponse {
     pub aggregates: Vec<GroupAggregate>,
 }
 
+/// Attempts per check-user request (transient failures only)
+const CHECK_USER_ATTEMPTS: u32 = 3;
+/// Delay before the first retry; doubled for each further attempt
+const CHECK_USER_RETRY_DELAY: Duration = Duration::from_millis(500);
+
 // Shared state for batching
 struct BatcherState {
     pending_user_ids: HashSet<String>,
@@ -54,13 +62,93 @@ impl BatcherState {
 
 static BATCHER_STATE: Mutex<Option<Arc<Mutex<BatcherState>>>> = Mutex::new(None);
 
+/// Running counters for check-user requests (shown as a backend health indicator)
+struct CheckStats {
+    processed_total: u64, // user ids checked successfully
+    failed_total: u64,    // requests that failed after retries
+    requests: u64,        // completed requests (success or failure), for the latency average
+    total_latency_ms: u64,
+    last_error: Option<String>,
+}
+
+static CHECK_STATS: Mutex<CheckStats> = Mutex::new(CheckStats {
+    processed_total: 0,
+    failed_total: 0,
+    requests: 0,
+    total_latency_ms: 0,
+    last_error: None,
+});
+
+const DEFAULT_DEBOUNCE_MS: u64 = 1000;
+const DEFAULT_MAX_BATCH: u64 = 25;
+
+/// Trailing debounce before a batch is sent (from settings, see set_group_check_batching)
+static DEBOUNCE_MS: AtomicU64 = AtomicU64::new(DEFAULT_DEBOUNCE_MS);
+/// Pending ids that trigger an immediate flush
+static MAX_BATCH: AtomicU64 = AtomicU64::new(DEFAULT_MAX_BATCH);
+/// Batches sent to the backend that haven't finished yet
+static IN_FLIGHT_BATCHES: AtomicUsize = AtomicUsize::new(0);
+
+fn clamp_debounce_ms(ms: Option<u64>) -> u64 {
+    ms.unwrap_or(DEFAULT_DEBOUNCE_MS).clamp(100, 10_000)
+}
+
+fn clamp_max_batch(max: Option<u64>) -> u64 {
+    max.unwrap_or(DEFAULT_MAX_BATCH).clamp(1, 500)
+}
+
+/// When the pending batch should be sent after a join
+#[derive(Debug, PartialEq)]
+enum FlushPlan {
+    /// The batch is full: send it without waiting for the joins to stop
+    Now,
+    /// Send once joins have been quiet for this long
+    After(Duration),
+}
+
+/// Decide when to flush `pending` ids, given the max batch size and debounce window
+fn plan_flush(pending: usize, max_batch: u64, debounce_ms: u64) -> FlushPlan {
+    if pending as u64 >= max_batch {
+        FlushPlan::Now
+    } else {
+        FlushPlan::After(Duration::from_millis(debounce_ms))
+    }
+}
+
 /// Initialize the batcher (called once at startup)
 pub fn init_batcher(_app_handle: AppHandle) -> Result<(), String> {
     let state = Arc::new(Mutex::new(BatcherState::new()));
     *BATCHER_STATE.lock().unwrap() = Some(state);
+    load_config();
     Ok(())
 }
 
+/// Load the debounce and max batch size from settings (startup and after a backup restore)
+pub fn load_config() {
+    if let Ok(s) = crate::modules::settings::settings::get_settings() {
+        DEBOUNCE_MS.store(clamp_debounce_ms(s.group_check_debounce_ms), Ordering::SeqCst);
+        MAX_BATCH.store(clamp_max_batch(s.group_check_max_batch), Ordering::SeqCst);
+    }
+}
+
+/// Set the group check debounce (ms, clamped to 100-10000) and max batch size (clamped to 1-500).
+/// Pass None for either to restore its default (1000ms / 25 ids). Returns the effective values.
+#[tauri::command]
+pub fn set_group_check_batching(debounce_ms: Option<u64>, max_batch: Option<u64>) -> Result<serde_json::Value, String> {
+    let effective_debounce = clamp_debounce_ms(debounce_ms);
+    let effective_max = clamp_max_batch(max_batch);
+    crate::modules::settings::settings::update_settings(|s| {
+        s.group_check_debounce_ms = debounce_ms.map(|_| effective_debounce);
+        s.group_check_max_batch = max_batch.map(|_| effective_max);
+    })?;
+    DEBOUNCE_MS.store(effective_debounce, Ordering::SeqCst);
+    MAX_BATCH.store(effective_max, Ordering::SeqCst);
+    Ok(serde_json::json!({
+        "debounceMs": effective_debounce,
+        "maxBatch": effective_max
+    }))
+}
+
 /// Add a user ID to the batch (called from frontend when player_joined event is received)
 #[tauri::command]
 pub fn add_user_to_batch_command(app_handle: AppHandle, user_id: String) -> Result<(), String> {
@@ -78,18 +166,28 @@ fn add_user_to_batch(app_handle: AppHandle, user_id: String) -> Result<(), Strin
     // Cancel existing timer by setting cancelled flag
     *state.cancelled.lock().unwrap() = true;
     
+    // Full batch: send now instead of waiting for the joins to stop
+    let pending = state.pending_user_ids.len();
+    let debounce = match plan_flush(pending, MAX_BATCH.load(Ordering::SeqCst), DEBOUNCE_MS.load(Ordering::SeqCst)) {
+        FlushPlan::Now => {
+            drop(state);
+            return flush_batch(app_handle, state_arc);
+        }
+        FlushPlan::After(debounce) => debounce,
+    };
+    
     // Create new cancellation flag for this timer
     let cancelled = Arc::new(Mutex::new(false));
     state.cancelled = cancelled.clone();
     
-    // Schedule new flush after 1 second using Tauri's async runtime
+    // Schedule new flush after the debounce window using Tauri's async runtime
     let app_clone = app_handle.clone();
     let state_clone = state_arc.clone();
     let cancelled_clone = cancelled.clone();
     
     async_runtime::spawn(async move {
         // Use tokio::time::sleep - this works because we're in Tauri's async runtime context
-        sleep(Duration::from_secs(1)).await;
+        sleep(debounce).await;
         
         // Check if cancelled
         if *cancelled_clone.lock().unwrap() {
@@ -115,26 +213,83 @@ fn flush_batch(app_handle: AppHandle, state_arc: Arc<Mutex<BatcherState>>) -> Re
     
     // Don't block - spawn async task using Tauri's runtime
     let app_clone = app_handle.clone();
+    IN_FLIGHT_BATCHES.fetch_add(1, Ordering::SeqCst);
     async_runtime::spawn(async move {
-        if let Err(e) = send_batch_to_server(app_clone.clone(), user_ids).await {
+        let result = send_batch_to_server(app_clone.clone(), user_ids).await;
+        IN_FLIGHT_BATCHES.fetch_sub(1, Ordering::SeqCst);
+        if let Err(e) = result {
             crate::debug_eprintln!("[batcher] Failed to send batch: {}", e);
+            crate::debug_error!(&app_clone, "[batcher] Failed to send batch: {}", e);
             let _ = app_clone.emit("group_watch_error", serde_json::json!({
                 "error": e
             }));
         }
+        let _ = app_clone.emit("group_check_stats", stats_json());
     });
     
     Ok(())
 }
 
-/// Send batch to server and emit results
-async fn send_batch_to_server(app_handle: AppHandle, user_ids: Vec<String>) -> Result<(), String> {
+/// User ids waiting for the next batch
+pub fn pending_count() -> usize {
+    BATCHER_STATE
+        .lock()
+        .ok()
+        .and_then(|s| s.clone())
+        .and_then(|state| state.lock().ok().map(|s| s.pending_user_ids.len()))
+        .unwrap_or(0)
+}
+
+/// Batches currently being sent to the backend
+pub fn in_flight_batches() -> usize {
+    IN_FLIGHT_BATCHES.load(Ordering::SeqCst)
+}
+
+/// Current counters plus the pending batch size
+fn stats_json() -> serde_json::Value {
+    let pending = pending_count();
+    let stats = CHECK_STATS.lock().unwrap();
+    let avg_latency_ms = if stats.requests > 0 { stats.total_latency_ms / stats.requests } else { 0 };
+    serde_json::json!({
+        "pending": pending,
+        "processedTotal": stats.processed_total,
+        "failedTotal": stats.failed_total,
+        "avgLatencyMs": avg_latency_ms,
+        "lastError": stats.last_error
+    })
+}
+
+/// check_users with the outcome counted in CHECK_STATS
+async fn check_users(user_ids: &[String]) -> Result<Option<CheckUserResponse>, String> {
+    let started = Instant::now();
+    let result = request_check_users(user_ids).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    
+    let mut stats = CHECK_STATS.lock().unwrap();
+    match &result {
+        Ok(None) => return result, // No tokens: no request was made
+        Ok(Some(_)) => stats.processed_total += user_ids.len() as u64,
+        Err(e) => {
+            stats.failed_total += 1;
+            stats.last_error = Some(e.clone());
+        }
+    }
+    stats.requests += 1;
+    stats.total_latency_ms += latency_ms;
+    drop(stats);
+    result
+}
+
+/// POST user ids to /check-user with all stored tokens, retrying transient failures
+/// (connection errors, 429 and 5xx) with backoff.
+/// Returns None when there are no usable tokens (nothing to check).
+async fn request_check_users(user_ids: &[String]) -> Result<Option<CheckUserResponse>, String> {
     // Get all stored tokens
     let tokens = crate::modules::group_auth::group_access_tokens::list_group_access_tokens()
         .map_err(|e| format!("Failed to get tokens: {}", e))?;
     
     if tokens.is_empty() {
-        return Ok(()); // No tokens, nothing to check
+        return Ok(None); // No tokens, nothing to check
     }
     
     let access_tokens: Vec<String> = tokens
@@ -144,12 +299,11 @@ async fn send_batch_to_server(app_handle: AppHandle, user_ids: Vec<String>) -> R
         .collect();
     
     if access_tokens.is_empty() {
-        return Ok(()); // No valid tokens
+        return Ok(None); // No valid tokens
     }
     
-    // API base URL - should match frontend
-    let api_base = std::env::var("VITE_API_BASE")
-        .unwrap_or_else(|_| "https://fch-toolkit.com".to_string());
+    // API base URL - should match frontend (settings override > VITE_API_BASE > default)
+    let api_base = crate::modules::settings::settings::api_base();
     
     let url = format!("{}/check-user", api_base);
     let payload = serde_json::json!({
@@ -158,23 +312,53 @@ async fn send_batch_to_server(app_handle: AppHandle, user_ids: Vec<String>) -> R
     });
     
     // Send HTTP request
-    let client = reqwest::Client::new();
-    let response = client
-        .post(&url)
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| format!("HTTP error: {}", e))?;
-    
-    if !response.status().is_success() {
-        return Err(format!("Server returned status: {}", response.status()));
-    }
+    let client = crate::modules::http_client::http_client::client();
+    let mut delay = CHECK_USER_RETRY_DELAY;
+    let mut attempt = 1;
+    let response = loop {
+        let request = client
+            .post(&url)
+            .json(&payload)
+            .timeout(crate::modules::http_client::http_client::API_TIMEOUT);
+        let (error, retryable) = match request.send().await {
+            Ok(response) if response.status().is_success() => break response,
+            Ok(response) => {
+                let status = response.status();
+                (
+                    format!("Server returned status: {}", status),
+                    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS,
+                )
+            }
+            Err(e) => (format!("HTTP error: {}", e), true),
+        };
+        if !retryable || attempt >= CHECK_USER_ATTEMPTS {
+            return Err(error);
+        }
+        crate::debug_eprintln!("[batcher] check-user attempt {} failed ({}), retrying in {:?}", attempt, error, delay);
+        sleep(delay).await;
+        delay *= 2;
+        attempt += 1;
+    };
     
     let result: CheckUserResponse = response
         .json()
         .await
         .map_err(|e| format!("Failed to parse response: {}", e))?;
     
+    // Keep the matches so the roster can be re-flagged after a page switch
+    if let Err(e) = super::group_matches::record_matches(&result.matches) {
+        crate::debug_eprintln!("[batcher] Failed to store group matches: {}", e);
+    }
+    
+    Ok(Some(result))
+}
+
+/// Send batch to server and emit results
+async fn send_batch_to_server(app_handle: AppHandle, user_ids: Vec<String>) -> Result<(), String> {
+    let Some(result) = check_users(&user_ids).await? else {
+        return Ok(());
+    };
+    
     // Emit results to frontend
     let _ = app_handle.emit("group_watch_results", serde_json::json!({
         "matches": result.matches,
@@ -184,6 +368,30 @@ async fn send_batch_to_server(app_handle: AppHandle, user_ids: Vec<String>) -> R
     Ok(())
 }
 
+/// Re-check a single user on demand (per-user "re-check" button).
+/// Returns { matches, aggregates } directly instead of emitting group_watch_results.
+#[tauri::command]
+pub async fn check_user_groups(user_id: String) -> Result<serde_json::Value, String> {
+    let user_id = user_id.trim().to_string();
+    if !crate::modules::log_reader::patterns::is_valid_user_id(&user_id) {
+        return Err(format!("Invalid user id: {}", user_id));
+    }
+    let result = check_users(std::slice::from_ref(&user_id))
+        .await?
+        .ok_or("No group access tokens stored")?;
+    Ok(serde_json::json!({
+        "matches": result.matches,
+        "aggregates": result.aggregates
+    }))
+}
+
+/// Check-user health: { pending, processedTotal, failedTotal, avgLatencyMs, lastError }
+/// (also emitted as group_check_stats after every batch)
+#[tauri::command]
+pub fn get_group_check_stats() -> Result<serde_json::Value, String> {
+    Ok(stats_json())
+}
+
 /// Manually trigger a batch flush (for testing or immediate checks)
 #[tauri::command]
 pub fn flush_user_batch() -> Result<String, String> {
@@ -198,3 +406,59 @@ pub fn flush_user_batch() -> Result<String, String> {
     // Note: This would need app_handle to actually flush, so this is just for status
     Ok(format!("{} users pending in batch", count))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `joins` distinct users through plan_flush the way add_user_to_batch does.
+    /// Returns (immediate flushes, ids still pending for the debounce timer).
+    fn simulate_rapid_joins(joins: usize, max_batch: u64, debounce_ms: u64) -> (usize, usize) {
+        let mut pending = 0;
+        let mut flushes = 0;
+        for _ in 0..joins {
+            pending += 1;
+            match plan_flush(pending, max_batch, debounce_ms) {
+                FlushPlan::Now => {
+                    flushes += 1;
+                    pending = 0;
+                }
+                FlushPlan::After(delay) => assert_eq!(delay, Duration::from_millis(debounce_ms)),
+            }
+        }
+        (flushes, pending)
+    }
+
+    #[test]
+    fn full_batch_flushes_now() {
+        assert_eq!(plan_flush(25, 25, 1000), FlushPlan::Now);
+        assert_eq!(plan_flush(30, 25, 1000), FlushPlan::Now);
+        assert_eq!(plan_flush(24, 25, 1000), FlushPlan::After(Duration::from_millis(1000)));
+        assert_eq!(plan_flush(1, 1, 250), FlushPlan::Now);
+    }
+
+    #[test]
+    fn fifty_rapid_joins_flush_at_least_once() {
+        let (flushes, pending) = simulate_rapid_joins(50, DEFAULT_MAX_BATCH, DEFAULT_DEBOUNCE_MS);
+        assert!(flushes >= 1);
+        assert_eq!((flushes, pending), (2, 0));
+
+        assert_eq!(simulate_rapid_joins(50, 20, 100), (2, 10));
+        assert_eq!(simulate_rapid_joins(50, 1, 100), (50, 0));
+    }
+
+    #[test]
+    fn large_max_batch_waits_for_debounce() {
+        assert_eq!(simulate_rapid_joins(50, clamp_max_batch(Some(500)), 5000), (0, 50));
+    }
+
+    #[test]
+    fn batching_config_is_clamped() {
+        assert_eq!(clamp_debounce_ms(None), DEFAULT_DEBOUNCE_MS);
+        assert_eq!(clamp_debounce_ms(Some(0)), 100);
+        assert_eq!(clamp_debounce_ms(Some(60_000)), 10_000);
+        assert_eq!(clamp_max_batch(None), DEFAULT_MAX_BATCH);
+        assert_eq!(clamp_max_batch(Some(0)), 1);
+        assert_eq!(clamp_max_batch(Some(10_000)), 500);
+    }
+}
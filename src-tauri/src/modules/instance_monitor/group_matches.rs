@@ -0,0 +1,170 @@
+// Group Matches: Persisted group watchlist results
+//
+// group_watch_results used to live only in the frontend, so switching pages lost which
+// present users matched which groups. Each match is stored with the instance it was
+// seen in (the instance's Joining timestamp), so the roster can be re-flagged later
+// without re-checking everyone.
+
+use rusqlite::{Connection, Result as SqlResult};
+use crate::modules::database::connection::app_db;
+use std::collections::HashSet;
+
+use super::batcher::GroupMatch;
+use crate::modules::log_reader::log_parser;
+
+/// Get or create database connection
+fn get_connection() -> SqlResult<Connection> {
+    let conn = app_db("fchapp.db")?;
+    
+    // instance_ts is '' when the match arrived outside a known instance
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS group_matches (
+            user_id TEXT NOT NULL,
+            group_id TEXT NOT NULL,
+            group_name TEXT,
+            watchlist INTEGER NOT NULL DEFAULT 0,
+            notifications INTEGER NOT NULL DEFAULT 0,
+            notes TEXT,
+            ts TEXT NOT NULL,
+            instance_ts TEXT NOT NULL DEFAULT '',
+            PRIMARY KEY (user_id, group_id, instance_ts)
+        )",
+        [],
+    )?;
+    
+    Ok(conn)
+}
+
+/// Initialize the table
+pub fn init_db() -> Result<(), String> {
+    get_connection().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Store matches from a check-user response against the current instance
+pub fn record_matches(matches: &[GroupMatch]) -> Result<(), String> {
+    if matches.is_empty() {
+        return Ok(());
+    }
+    let instance_ts = log_parser::get_current_instance_timestamp().unwrap_or_default();
+    let now = log_parser::now_log_ts();
+    
+    let mut conn = get_connection().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for m in matches {
+        tx.execute(
+            "INSERT OR REPLACE INTO group_matches
+                (user_id, group_id, group_name, watchlist, notifications, notes, ts, instance_ts)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                m.user_id,
+                m.group_id,
+                m.group_name,
+                m.watchlist,
+                m.notifications,
+                m.notes,
+                now,
+                instance_ts
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// Names of the groups a user has matched (any instance), most recent first
+pub fn group_names_for_user(user_id: &str) -> Result<Vec<String>, String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(NULLIF(group_name, ''), group_id), MAX(ts) AS last_ts
+             FROM group_matches WHERE user_id = ?1
+             GROUP BY group_id ORDER BY last_ts DESC"
+        )
+        .map_err(|e| e.to_string())?;
+    let names = stmt
+        .query_map([user_id], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(names)
+}
+
+/// Matches for users currently in the instance (same shape as group_watch_results matches)
+#[tauri::command]
+pub fn get_active_group_matches() -> Result<Vec<GroupMatch>, String> {
+    let Some(instance_ts) = log_parser::get_current_instance_timestamp() else {
+        return Ok(Vec::new());
+    };
+    let present: HashSet<String> = log_parser::get_active_player_ids().into_iter().collect();
+    if present.is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT user_id, group_id, group_name, watchlist, notes, notifications
+             FROM group_matches WHERE instance_ts = ?1 ORDER BY ts ASC"
+        )
+        .map_err(|e| e.to_string())?;
+    let matches = stmt
+        .query_map([instance_ts], |row| {
+            Ok(GroupMatch {
+                user_id: row.get(0)?,
+                group_id: row.get(1)?,
+                group_name: row.get(2)?,
+                watchlist: row.get(3)?,
+                notes: row.get(4)?,
+                notifications: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    
+    Ok(matches.into_iter().filter(|m| present.contains(&m.user_id)).collect())
+}
+
+/// Past group watchlist matches (watchlist flag set), newest first, optionally for one user.
+/// Each row carries the instance it was seen in; location/roomName come from the session
+/// roster snapshot and are null for the current instance or sessions with no snapshot.
+#[tauri::command]
+pub fn get_group_watchlisted_history(user_id: Option<String>, limit: Option<u32>) -> Result<Vec<serde_json::Value>, String> {
+    let user_id = user_id.map(|id| id.trim().to_string()).filter(|id| !id.is_empty());
+    let limit = limit.unwrap_or(200).clamp(1, 5000);
+    let current_ts = log_parser::get_current_instance_timestamp();
+    
+    // Make sure the roster table exists so the join below can't fail on a fresh database
+    crate::modules::history::session_rosters::init_db()?;
+    
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.user_id, m.group_id, m.group_name, m.notes, m.ts, m.instance_ts,
+                    r.location, r.room_name
+             FROM group_matches m
+             LEFT JOIN session_rosters r ON r.instance_ts = m.instance_ts AND m.instance_ts != ''
+             WHERE m.watchlist = 1 AND (?1 IS NULL OR m.user_id = ?1)
+             ORDER BY m.ts DESC
+             LIMIT ?2"
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![user_id, limit], |row| {
+            let instance_ts: String = row.get(5)?;
+            Ok(serde_json::json!({
+                "userId": row.get::<_, String>(0)?,
+                "groupId": row.get::<_, String>(1)?,
+                "groupName": row.get::<_, Option<String>>(2)?,
+                "notes": row.get::<_, Option<String>>(3)?,
+                "ts": row.get::<_, String>(4)?,
+                "isCurrentInstance": current_ts.as_deref() == Some(instance_ts.as_str()),
+                "instanceTs": if instance_ts.is_empty() { None } else { Some(instance_ts) },
+                "location": row.get::<_, Option<String>>(6)?,
+                "roomName": row.get::<_, Option<String>>(7)?,
+            }))
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<SqlResult<Vec<_>>>().map_err(|e| e.to_string())
+}
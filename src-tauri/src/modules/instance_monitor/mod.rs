@@ -1,2 +1,3 @@
 // Instance Monitor module
 pub mod batcher;
+pub mod group_matches;
@@ -9,7 +9,12 @@
 // - Provide simple Tauri commands for the front-end to read/write.
 
 use std::{fs, path::PathBuf};
+use std::sync::Mutex;
 use serde::{Deserialize, Serialize};
+use crate::modules::database::connection;
+
+/// Serializes load-modify-save cycles so concurrent commands can't drop each other's changes
+static NOTES_LOCK: Mutex<()> = Mutex::new(());
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct UserNotes {
@@ -40,15 +45,16 @@ pub struct Note {
 pub fn notes_dir() -> PathBuf {
     // Choose a stable per-user folder (LocalAppData\FCHClient on Windows).
     // This is shared by dev and release unless you differentiate elsewhere.
-    let base = std::env::var("LOCALAPPDATA")
-        .ok()
-        .map(PathBuf::from)
-        .unwrap_or_else(|| PathBuf::from("C:/Users/Public"));
-    base.join("FCHClient")
+    connection::data_dir()
 }
 
 /// Get the path to the notes.json file
 fn notes_path() -> PathBuf {
+    // Tests point this at a temp folder so they never touch the real notes.json
+    #[cfg(test)]
+    if let Some(dir) = tests::TEST_DIR.get() {
+        return dir.join("notes.json");
+    }
     // Single JSON file that contains all notes/watchlist/usernames
     notes_dir().join("notes.json")
 }
@@ -83,13 +89,28 @@ pub fn load_all_notes() -> UserNotes {
 /// Save all notes to disk
 pub fn save_all_notes(notes: &UserNotes) -> Result<(), String> {
     // Ensure the folder exists, then write a pretty JSON snapshot.
-    let dir = notes_dir();
-    if let Err(e) = fs::create_dir_all(&dir) {
-        return Err(e.to_string());
-    }
     let p = notes_path();
+    if let Some(dir) = p.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
     let data = serde_json::to_vec_pretty(notes).map_err(|e| e.to_string())?;
-    fs::write(p, data).map_err(|e| e.to_string())
+    // Write a temp file and rename it over notes.json, so a crash or a concurrent
+    // reader never sees a half-written file (which load_all_notes would treat as empty)
+    let tmp = p.with_extension("json.tmp");
+    fs::write(&tmp, data).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, &p).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        e.to_string()
+    })
+}
+
+/// Load, modify and save notes.json while holding the notes lock.
+/// Every write should go through this rather than pairing load_all_notes/save_all_notes.
+pub fn update_notes<F: FnOnce(&mut UserNotes)>(f: F) -> Result<(), String> {
+    let _guard = NOTES_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut all = load_all_notes();
+    f(&mut all);
+    save_all_notes(&all)
 }
 
 /// Initialize the notes database - creates file if it doesn't exist
@@ -97,6 +118,7 @@ pub fn init_notes_db() -> Result<(), String> {
     let p = notes_path();
     
     // If file doesn't exist, create it with default empty structure
+    let _guard = NOTES_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
     if !p.exists() {
         let default_notes = UserNotes::default();
         save_all_notes(&default_notes)?;
@@ -112,13 +134,13 @@ pub fn add_note(user_id: String, text: String) -> Result<(), String> {
         return Err("user_id required".into());
     }
     let ts = chrono::Local::now().format("%Y.%m.%d %H:%M:%S").to_string();
-    let mut all = load_all_notes();
-    // We currently store only the latest note; keeping a Vec preserves the timestamp
-    // structure and makes it easy to extend to multiple notes later.
-    let entry = all.notes.entry(user_id).or_default();
-    entry.clear();
-    entry.push(Note { ts, text });
-    save_all_notes(&all)
+    update_notes(|all| {
+        // We currently store only the latest note; keeping a Vec preserves the timestamp
+        // structure and makes it easy to extend to multiple notes later.
+        let entry = all.notes.entry(user_id).or_default();
+        entry.clear();
+        entry.push(Note { ts, text });
+    })
 }
 
 // Note: get_notes is kept for potential future use but not currently exposed as a Tauri command
@@ -159,12 +181,12 @@ pub fn delete_user(user_id: String) -> Result<(), String> {
     if user_id.trim().is_empty() {
         return Err("user_id required".into());
     }
-    let mut all = load_all_notes();
-    all.notes.remove(&user_id);
-    all.watchlist.remove(&user_id);
-    all.usernames.remove(&user_id);
-    all.sounds.remove(&user_id);
-    save_all_notes(&all)
+    update_notes(|all| {
+        all.notes.remove(&user_id);
+        all.watchlist.remove(&user_id);
+        all.usernames.remove(&user_id);
+        all.sounds.remove(&user_id);
+    })
 }
 
 #[tauri::command]
@@ -189,9 +211,9 @@ pub fn set_watch(user_id: String, watch: bool) -> Result<(), String> {
     if user_id.trim().is_empty() {
         return Err("user_id required".into());
     }
-    let mut all = load_all_notes();
-    all.watchlist.insert(user_id, watch);
-    save_all_notes(&all)
+    update_notes(|all| {
+        all.watchlist.insert(user_id, watch);
+    })
 }
 
 #[tauri::command]
@@ -210,16 +232,16 @@ pub fn set_user_sound(user_id: String, path: Option<String>) -> Result<(), Strin
     if user_id.trim().is_empty() {
         return Err("user_id required".into());
     }
-    let mut all = load_all_notes();
-    match path.and_then(|p| if p.trim().is_empty() { None } else { Some(p) }) {
-        Some(p) => {
-            all.sounds.insert(user_id, p);
-        }
-        None => {
-            all.sounds.remove(&user_id);
+    update_notes(|all| {
+        match path.and_then(|p| if p.trim().is_empty() { None } else { Some(p) }) {
+            Some(p) => {
+                all.sounds.insert(user_id, p);
+            }
+            None => {
+                all.sounds.remove(&user_id);
+            }
         }
-    }
-    save_all_notes(&all)
+    })
 }
 
 #[tauri::command]
@@ -236,14 +258,43 @@ pub fn set_username(user_id: String, username: String) -> Result<(), String> {
     if user_id.trim().is_empty() {
         return Err("user_id required".into());
     }
-    let mut all = load_all_notes();
-    let effective = if username.trim().is_empty() {
-        "Not Yet Recorded".to_string()
-    } else {
-        username
-    };
-    all.usernames.insert(user_id, effective);
-    save_all_notes(&all)
+    update_notes(|all| {
+        let effective = if username.trim().is_empty() {
+            "Not Yet Recorded".to_string()
+        } else {
+            username
+        };
+        all.usernames.insert(user_id, effective);
+    })
+}
+
+#[tauri::command]
+pub fn add_watch_by_id(user_id: String, username: Option<String>, note: Option<String>) -> Result<(), String> {
+    // Pre-flag a user from an id obtained elsewhere, so the alert fires on their first join.
+    let user_id = user_id.trim().to_string();
+    if !crate::modules::log_reader::patterns::is_valid_user_id(&user_id) {
+        return Err(format!("Invalid user id: {} (expected usr_...)", user_id));
+    }
+    update_notes(|all| {
+        all.watchlist.insert(user_id.clone(), true);
+        // Keep a name we already know unless a new one was given
+        match username.filter(|u| !u.trim().is_empty()) {
+            Some(name) => {
+                all.usernames.insert(user_id.clone(), name);
+            }
+            None => {
+                all.usernames
+                    .entry(user_id.clone())
+                    .or_insert_with(|| "Not Yet Recorded".to_string());
+            }
+        }
+        if let Some(text) = note.filter(|t| !t.trim().is_empty()) {
+            let ts = chrono::Local::now().format("%Y.%m.%d %H:%M:%S").to_string();
+            let entry = all.notes.entry(user_id).or_default();
+            entry.clear();
+            entry.push(Note { ts, text });
+        }
+    })
 }
 
 #[tauri::command]
@@ -253,3 +304,45 @@ pub fn browse_sound() -> Result<serde_json::Value, String> {
         .pick_file();
     Ok(serde_json::json!({ "path": file.map(|p| p.to_string_lossy().to_string()) }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::OnceLock;
+
+    /// Folder notes_path resolves to under test
+    pub(super) static TEST_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+    #[test]
+    fn concurrent_updates_keep_every_entry_and_valid_json() {
+        let dir = std::env::temp_dir().join(format!("fch-notes-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        TEST_DIR.set(dir.clone()).expect("test dir set twice");
+
+        const THREADS: usize = 8;
+        const WRITES_PER_THREAD: usize = 25;
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                std::thread::spawn(move || {
+                    for i in 0..WRITES_PER_THREAD {
+                        let user_id = format!("usr_{:04}{:04}", t, i);
+                        update_notes(|all| {
+                            all.usernames.insert(user_id.clone(), format!("user {} {}", t, i));
+                        })
+                        .expect("update_notes failed");
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("writer thread panicked");
+        }
+
+        let data = fs::read(dir.join("notes.json")).expect("notes.json missing");
+        let notes: UserNotes = serde_json::from_slice(&data).expect("notes.json is not valid JSON");
+        assert_eq!(notes.usernames.len(), THREADS * WRITES_PER_THREAD);
+        assert!(!dir.join("notes.json.tmp").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
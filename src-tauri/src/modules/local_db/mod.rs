@@ -1 +1,2 @@
 pub mod localdb;
+pub mod user_summary;
@@ -0,0 +1,70 @@
+// User Summary: One formatted block of everything we know about a user
+//
+// Packages the username cache, note, watch flag, first/last sighting and stored
+// group matches into plain text for pasting into Discord. Empty fields are left out.
+
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::modules::history::first_seen;
+use crate::modules::instance_monitor::group_matches;
+use crate::modules::local_db::localdb;
+use crate::modules::log_reader::log_parser;
+use crate::modules::watchlist::watchlist_hits;
+
+/// Build the summary text for a user
+fn build_summary(user_id: &str) -> Result<String, String> {
+    let notes = localdb::load_all_notes();
+    let mut lines: Vec<String> = Vec::new();
+    
+    let username = notes
+        .usernames
+        .get(user_id)
+        .filter(|name| !name.trim().is_empty() && name.as_str() != "Not Yet Recorded");
+    if let Some(name) = username {
+        lines.push(format!("User: {}", name));
+    }
+    lines.push(format!("ID: {}", user_id));
+    
+    if notes.watchlist.get(user_id).copied().unwrap_or(false) {
+        lines.push("Watchlist: yes".to_string());
+    }
+    
+    let present = log_parser::get_active_players()
+        .into_iter()
+        .find(|p| p["userId"].as_str() == Some(user_id));
+    if let Some(player) = present {
+        lines.push(format!("Last seen: in instance now (joined {})", player["joinedAt"].as_str().unwrap_or("?")));
+    } else if let Some(hit) = watchlist_hits::list_watchlist_hits(Some(1), Some(user_id.to_string()))?.into_iter().next() {
+        lines.push(format!("Last seen: {}", hit.timestamp));
+    }
+    if let Some(first) = first_seen::get_first_seen(user_id.to_string())? {
+        lines.push(format!("First seen: {}", first));
+    }
+    
+    let groups = group_matches::group_names_for_user(user_id)?;
+    if !groups.is_empty() {
+        lines.push(format!("Group watchlists: {}", groups.join(", ")));
+    }
+    
+    if let Some(note) = notes.notes.get(user_id).and_then(|v| v.last()).filter(|n| !n.text.trim().is_empty()) {
+        lines.push(format!("Note ({}): {}", note.ts, note.text.trim()));
+    }
+    
+    Ok(lines.join("\n"))
+}
+
+/// Copy a formatted summary of a user to the clipboard and return the copied text
+#[tauri::command]
+pub fn copy_user_summary(app_handle: AppHandle, user_id: String) -> Result<String, String> {
+    let user_id = user_id.trim().to_string();
+    if user_id.is_empty() {
+        return Err("user_id required".into());
+    }
+    let text = build_summary(&user_id)?;
+    app_handle
+        .clipboard()
+        .write_text(text.clone())
+        .map_err(|e| format!("Failed to write to clipboard: {}", e))?;
+    Ok(text)
+}
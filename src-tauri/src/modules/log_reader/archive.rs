@@ -0,0 +1,60 @@
+// Archive: Transparent access to gzip-archived logs (output_log_*.txt.gz)
+//
+// Gzip streams can't be seeked, so whole-file readers decompress on the fly,
+// while the line index (which seeks) reads from a decompressed copy in the temp
+// folder. The copy is made once per archive and refreshed if the archive changes.
+// Plain .txt logs are never touched by any of this.
+
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use flate2::read::GzDecoder;
+
+/// True for gzip-compressed logs (by extension)
+pub fn is_gzip(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("gz"))
+}
+
+/// Read a whole log file, decompressing .gz archives
+pub fn read_log_bytes(path: &Path) -> Result<Vec<u8>, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut bytes = Vec::new();
+    if is_gzip(path) {
+        GzDecoder::new(file)
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to decompress {}: {}", path.display(), e))?;
+    } else {
+        file.read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    }
+    Ok(bytes)
+}
+
+/// Path to read with seeks: the file itself, or a decompressed temp copy for .gz archives
+pub fn seekable_path(path: &Path) -> Result<PathBuf, String> {
+    if !is_gzip(path) {
+        return Ok(path.to_path_buf());
+    }
+    
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    path.hash(&mut hasher);
+    let cache_dir = std::env::temp_dir().join("fch-log-archive-cache");
+    let copy = cache_dir.join(format!("{:016x}.txt", hasher.finish()));
+    
+    let archive_modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+    let copy_modified = fs::metadata(&copy).and_then(|m| m.modified()).ok();
+    let fresh = matches!((archive_modified, copy_modified), (Some(a), Some(c)) if c >= a);
+    if !fresh {
+        fs::create_dir_all(&cache_dir).map_err(|e| format!("Failed to create archive cache: {}", e))?;
+        // Decompress to a partial file first so an interrupted copy is never mistaken for a fresh one
+        let partial = copy.with_extension("partial");
+        let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let mut out = File::create(&partial).map_err(|e| format!("Failed to create archive cache file: {}", e))?;
+        std::io::copy(&mut GzDecoder::new(file), &mut out)
+            .map_err(|e| format!("Failed to decompress {}: {}", path.display(), e))?;
+        drop(out);
+        fs::rename(&partial, &copy).map_err(|e| format!("Failed to create archive cache file: {}", e))?;
+    }
+    Ok(copy)
+}
@@ -1,2 +1,162 @@
-// Event Exporter: Exports parsed events to various destinations
-// Placeholder for future implementation
+// Event Exporter: The one place parsed log events are shaped for external consumers
+//
+// export_event turns a ParsedEvent into a versioned JSON object. The same shape is
+// written to events.jsonl (when event_export_enabled is on) and served by the local
+// HTTP API (GET /events), so downstream tooling can rely on it.
+//
+// Schema (schemaVersion 1). Every event has:
+//   schemaVersion  number   bumped only on breaking changes
+//   type           string   one of the types below
+//   timestamp      string   log timestamp, YYYY.MM.DD HH:MM:SS (log timezone)
+//   file           string   log file name the line came from
+// Per type:
+//   player_joined / player_left   username, userId
+//   joining_world                 worldId, instanceId, instanceType, groupId, region (each may be null)
+//   joining_room                  roomName
+//   joined_room / left_room       (no extra fields)
+//   moderation                    admin, action ("ban" | "warn"), target, reason
+//   authenticated                 username, userId (the local user)
+// New optional fields may be added without a version bump.
+//
+// The file export is off by default. Each line is a complete JSON value; when the file
+// reaches its size cap it is renamed to "events.jsonl.1" (replacing any older rotation)
+// and a fresh file is started.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::modules::debug::log_file::RotatingFile;
+use crate::modules::database::connection;
+use crate::modules::log_reader::log_parser::ParsedEvent;
+use crate::modules::settings::settings;
+
+/// Version of the exported event shape (see the schema above)
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// How many recent events are kept in memory for the HTTP API
+const RECENT_EVENTS_MAX: usize = 500;
+
+const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024; // 10MB
+
+/// Whether events are also appended to events.jsonl
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Path of the active export file
+pub fn export_path() -> PathBuf {
+    connection::data_dir().join("events.jsonl")
+}
+
+static SINK: Mutex<Option<RotatingFile>> = Mutex::new(None);
+
+fn configure(enabled: bool, max_bytes: Option<u64>) {
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_MAX_BYTES);
+    if let Ok(mut sink) = SINK.lock() {
+        *sink = enabled.then(|| RotatingFile::new(export_path(), max_bytes));
+    }
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+static RECENT_EVENTS: Mutex<VecDeque<serde_json::Value>> = Mutex::new(VecDeque::new());
+
+/// Serialize a parsed event into the versioned schema (Null for lines we don't track).
+/// timestamp and file are added by record, since they come from the line, not the event.
+pub(crate) fn export_event(event: &ParsedEvent) -> serde_json::Value {
+    let mut value = match event {
+        ParsedEvent::Player { joined, username, user_id } => serde_json::json!({
+            "type": ParsedEvent::player_event_kind(*joined),
+            "username": username,
+            "userId": user_id
+        }),
+        ParsedEvent::JoiningWorld { world_id, instance_id, tags } => serde_json::json!({
+            "type": "joining_world",
+            "worldId": world_id,
+            "instanceId": instance_id,
+            "instanceType": tags.instance_type,
+            "groupId": tags.group_id,
+            "region": tags.region
+        }),
+        ParsedEvent::JoiningRoom { room_name } => serde_json::json!({
+            "type": "joining_room",
+            "roomName": room_name
+        }),
+        ParsedEvent::JoinedRoom => serde_json::json!({ "type": "joined_room" }),
+        ParsedEvent::LeftRoom => serde_json::json!({ "type": "left_room" }),
+        ParsedEvent::Moderation { admin, action, target, reason } => serde_json::json!({
+            "type": "moderation",
+            "admin": admin,
+            "action": action,
+            "target": target,
+            "reason": reason
+        }),
+        ParsedEvent::Authenticated { username, user_id } => serde_json::json!({
+            "type": "authenticated",
+            "username": username,
+            "userId": user_id
+        }),
+        ParsedEvent::Other => return serde_json::Value::Null,
+    };
+    value["schemaVersion"] = serde_json::json!(SCHEMA_VERSION);
+    value
+}
+
+/// Record a live event: kept for the HTTP API and appended to events.jsonl when enabled
+pub(crate) fn record(event: &ParsedEvent, timestamp: &str, file: &str) {
+    let mut value = export_event(event);
+    if value.is_null() {
+        return;
+    }
+    value["timestamp"] = serde_json::json!(timestamp);
+    value["file"] = serde_json::json!(file);
+    if ENABLED.load(Ordering::SeqCst) {
+        append(&value);
+    }
+    if let Ok(mut recent) = RECENT_EVENTS.lock() {
+        if recent.len() >= RECENT_EVENTS_MAX {
+            recent.pop_front();
+        }
+        recent.push_back(value);
+    }
+}
+
+/// Most recent exported events, oldest first
+pub fn recent_events(limit: usize) -> Vec<serde_json::Value> {
+    RECENT_EVENTS
+        .lock()
+        .map(|recent| {
+            let skip = recent.len().saturating_sub(limit);
+            recent.iter().skip(skip).cloned().collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Append one event as a JSON line (no-op when the export is disabled)
+fn append(event: &serde_json::Value) {
+    if let Ok(mut guard) = SINK.lock() {
+        if let Some(sink) = guard.as_mut() {
+            if let Err(e) = sink.write_line(&event.to_string()) {
+                crate::debug_eprintln!("[event_exporter] Failed to write events.jsonl: {}", e);
+            }
+        }
+    }
+}
+
+/// Restore the export from settings (startup and after a backup restore)
+pub fn init_from_settings() -> Result<(), String> {
+    let settings = settings::get_settings()?;
+    configure(settings.event_export_enabled, settings.event_export_max_bytes);
+    Ok(())
+}
+
+/// Enable or disable the events.jsonl export. max_bytes caps the file size before
+/// rotation (default 10MB). Returns the export file path.
+#[tauri::command]
+pub fn set_event_export(enabled: bool, max_bytes: Option<u64>) -> Result<String, String> {
+    settings::update_settings(|s| {
+        s.event_export_enabled = enabled;
+        s.event_export_max_bytes = max_bytes;
+    })?;
+    configure(enabled, max_bytes);
+    Ok(export_path().to_string_lossy().to_string())
+}
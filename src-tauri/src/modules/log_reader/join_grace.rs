@@ -0,0 +1,136 @@
+// Join Grace: Collapse connect-then-disconnect flicker during instance transitions
+//
+// With a grace period set, a live join is held back for that many seconds. If the
+// same user leaves before it is published (by log time), the join and the leave
+// are both dropped. The backfill applies the same rule to its replayed events.
+// Off by default (0) because it delays join alerts by the grace period.
+
+use crate::modules::log_reader::log_parser;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Largest grace period accepted (seconds)
+const MAX_GRACE_SECS: u64 = 60;
+
+/// Grace period in seconds (0 = off, from settings, see set_join_grace_secs)
+static GRACE_SECS: AtomicU64 = AtomicU64::new(0);
+
+/// A join waiting out the grace period, with what's needed to publish it later
+pub struct HeldJoin {
+    pub user_id: String,
+    pub username: String,
+    pub join_ts: String,
+    pub line: String,
+    pub file_name: String,
+    held_at: Instant,
+}
+
+lazy_static! {
+    /// Joins waiting out the grace period, by user id
+    static ref HELD_JOINS: Mutex<HashMap<String, HeldJoin>> = Mutex::new(HashMap::new());
+}
+
+/// Load the grace period from settings (startup and after a backup restore)
+pub fn load_config() {
+    if let Ok(s) = crate::modules::settings::settings::get_settings() {
+        GRACE_SECS.store(s.join_grace_secs.unwrap_or(0).min(MAX_GRACE_SECS), Ordering::SeqCst);
+    }
+}
+
+/// Current grace period in seconds (0 = off)
+pub fn grace_secs() -> u64 {
+    GRACE_SECS.load(Ordering::SeqCst)
+}
+
+/// True if a leave at leave_ts came within the grace period of a join at join_ts (log time)
+pub fn is_transient(join_ts: &str, leave_ts: &str) -> bool {
+    let grace = grace_secs();
+    if grace == 0 {
+        return false;
+    }
+    match (log_parser::parse_log_ts(join_ts), log_parser::parse_log_ts(leave_ts)) {
+        (Some(joined), Some(left)) => (left - joined).num_seconds() < grace as i64,
+        _ => false,
+    }
+}
+
+/// Hold a join until the grace period passes (a newer join from the same user replaces it)
+pub fn hold_join(user_id: &str, username: &str, join_ts: &str, line: &str, file_name: &str) {
+    if let Ok(mut held) = HELD_JOINS.lock() {
+        held.insert(user_id.to_string(), HeldJoin {
+            user_id: user_id.to_string(),
+            username: username.to_string(),
+            join_ts: join_ts.to_string(),
+            line: line.to_string(),
+            file_name: file_name.to_string(),
+            held_at: Instant::now(),
+        });
+    }
+}
+
+/// Leave side: take the user's held join, if any
+pub fn take_held_join(user_id: &str) -> Option<HeldJoin> {
+    HELD_JOINS.lock().ok()?.remove(user_id)
+}
+
+/// True once a held join can no longer be collapsed: it has been held for the grace period,
+/// or the line being processed (log_ts) is already past it by log time (reader catching up)
+fn is_expired(join: &HeldJoin, grace: Duration, log_ts: Option<&str>) -> bool {
+    if join.held_at.elapsed() >= grace {
+        return true;
+    }
+    match log_ts.and_then(log_parser::parse_log_ts).zip(log_parser::parse_log_ts(&join.join_ts)) {
+        Some((now, joined)) => (now - joined).num_seconds() >= grace.as_secs() as i64,
+        None => false,
+    }
+}
+
+/// Take the held joins whose grace period is over, oldest join first
+pub fn take_expired(log_ts: Option<&str>) -> Vec<HeldJoin> {
+    let grace = Duration::from_secs(grace_secs());
+    let Ok(mut held) = HELD_JOINS.lock() else {
+        return Vec::new();
+    };
+    let expired: Vec<String> = held
+        .values()
+        .filter(|join| is_expired(join, grace, log_ts))
+        .map(|join| join.user_id.clone())
+        .collect();
+    let mut joins: Vec<HeldJoin> = expired.iter().filter_map(|id| held.remove(id)).collect();
+    joins.sort_by_key(|join| join.held_at);
+    joins
+}
+
+/// Time until the next held join's grace period is over; None when nothing is held
+pub fn next_deadline() -> Option<Duration> {
+    let grace = Duration::from_secs(grace_secs());
+    HELD_JOINS
+        .lock()
+        .ok()?
+        .values()
+        .map(|join| grace.saturating_sub(join.held_at.elapsed()))
+        .min()
+}
+
+/// Drop all held joins without publishing them (instance change)
+pub fn clear() {
+    if let Ok(mut held) = HELD_JOINS.lock() {
+        held.clear();
+    }
+}
+
+/// Set the join grace period in seconds (0 or None = off, clamped to 60).
+/// Joins whose matching leave comes within this window are not shown at all;
+/// every other join is shown after the delay. Returns the effective value.
+#[tauri::command]
+pub fn set_join_grace_secs(secs: Option<u64>) -> Result<u64, String> {
+    let effective = secs.unwrap_or(0).min(MAX_GRACE_SECS);
+    crate::modules::settings::settings::update_settings(|s| {
+        s.join_grace_secs = Some(effective).filter(|v| *v > 0);
+    })?;
+    GRACE_SECS.store(effective, Ordering::SeqCst);
+    Ok(effective)
+}
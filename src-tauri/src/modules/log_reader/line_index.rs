@@ -0,0 +1,145 @@
+// Line Index: Line counts and line-based access for log files
+//
+// For each file we keep the byte offset where every line starts, found by scanning
+// raw bytes in chunks (no per-line allocation). VRChat logs only ever grow, so when
+// a file gets bigger the index is extended from where it left off; a file that
+// shrank was replaced and is indexed again from scratch.
+// Archived .txt.gz logs are indexed through a decompressed temp copy (see archive.rs).
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use lazy_static::lazy_static;
+
+use crate::modules::log_reader::{archive, log_parser, log_reader};
+
+const SCAN_CHUNK_SIZE: usize = 256 * 1024;
+/// Number of files whose index is kept in memory
+const MAX_CACHED_INDEXES: usize = 4;
+
+struct LineIndex {
+    /// File size the index covers
+    size: u64,
+    /// Byte offset of the start of each line (first entry is always 0)
+    line_starts: Vec<u64>,
+}
+
+impl LineIndex {
+    /// Number of lines (a trailing newline doesn't start a new, empty line)
+    fn line_count(&self) -> u64 {
+        match self.line_starts.last() {
+            Some(&last) if last < self.size => self.line_starts.len() as u64,
+            _ => self.line_starts.len().saturating_sub(1) as u64,
+        }
+    }
+}
+
+lazy_static! {
+    static ref INDEXES: Mutex<HashMap<PathBuf, LineIndex>> = Mutex::new(HashMap::new());
+}
+
+/// Explicit path, else the file the log reader is following, else the newest log on disk
+pub(crate) fn resolve_log_path(path: Option<String>) -> Result<PathBuf, String> {
+    if let Some(p) = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) {
+        return Ok(PathBuf::from(p));
+    }
+    if let Some(p) = log_reader::get_most_recent_log_file()? {
+        return Ok(PathBuf::from(p));
+    }
+    log_parser::find_latest_log_file()?.ok_or_else(|| "No VRChat log file found".to_string())
+}
+
+/// Bring the index for path up to date with the file on disk and run f on it
+fn with_index<T>(path: &PathBuf, f: impl FnOnce(&LineIndex) -> T) -> Result<T, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let size = file.metadata().map_err(|e| format!("Failed to read metadata: {}", e))?.len();
+    
+    let mut indexes = INDEXES.lock().map_err(|_| "Failed to lock line index".to_string())?;
+    if !indexes.contains_key(path) && indexes.len() >= MAX_CACHED_INDEXES {
+        indexes.clear();
+    }
+    let index = indexes.entry(path.clone()).or_insert_with(|| LineIndex { size: 0, line_starts: vec![0] });
+    if size < index.size {
+        // Truncated or replaced: start over
+        index.size = 0;
+        index.line_starts = vec![0];
+    }
+    
+    if size > index.size {
+        file.seek(SeekFrom::Start(index.size))
+            .map_err(|e| format!("Failed to seek in file: {}", e))?;
+        let mut reader = file.take(size - index.size);
+        let mut buf = vec![0u8; SCAN_CHUNK_SIZE];
+        let mut pos = index.size;
+        loop {
+            let n = reader.read(&mut buf).map_err(|e| format!("Failed to read file: {}", e))?;
+            if n == 0 {
+                break;
+            }
+            for (i, byte) in buf[..n].iter().enumerate() {
+                if *byte == b'\n' {
+                    index.line_starts.push(pos + i as u64 + 1);
+                }
+            }
+            pos += n as u64;
+        }
+        index.size = pos;
+    }
+    
+    Ok(f(index))
+}
+
+/// Total number of lines in a log file (defaults to the current log).
+/// Cached per file; only newly appended bytes are scanned on later calls.
+#[tauri::command]
+pub fn get_log_line_count(path: Option<String>) -> Result<u64, String> {
+    let path = archive::seekable_path(&resolve_log_path(path)?)?;
+    with_index(&path, |index| index.line_count())
+}
+
+/// Most lines read_log_lines returns per call
+const MAX_LINES_PER_READ: usize = 5000;
+
+/// Read count lines starting at line start_line (0-based) from a log file (defaults to the
+/// current log). Uses the line index to seek straight to the range.
+/// Returns { path, startLine, lines, byteOffset, totalLines }.
+#[tauri::command]
+pub fn read_log_lines(start_line: usize, count: usize, path: Option<String>) -> Result<serde_json::Value, String> {
+    let requested = resolve_log_path(path)?;
+    let path = archive::seekable_path(&requested)?;
+    let count = count.min(MAX_LINES_PER_READ);
+    
+    let (start_offset, end_offset, total_lines) = with_index(&path, |index| {
+        let total = index.line_count() as usize;
+        let start = start_line.min(total);
+        let end = start.saturating_add(count).min(total);
+        let start_offset = index.line_starts.get(start).copied().unwrap_or(index.size);
+        let end_offset = index.line_starts.get(end).copied().unwrap_or(index.size).min(index.size);
+        (start_offset, end_offset, total)
+    })?;
+    
+    let mut lines: Vec<String> = Vec::new();
+    if end_offset > start_offset {
+        let mut file = File::open(&path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        file.seek(SeekFrom::Start(start_offset))
+            .map_err(|e| format!("Failed to seek in file: {}", e))?;
+        let mut buf = Vec::with_capacity((end_offset - start_offset) as usize);
+        file.take(end_offset - start_offset)
+            .read_to_end(&mut buf)
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        lines = String::from_utf8_lossy(&buf)
+            .lines()
+            .map(|l| l.to_string())
+            .collect();
+    }
+    
+    Ok(serde_json::json!({
+        "path": requested.to_string_lossy(),
+        "startLine": start_line.min(total_lines),
+        "lines": lines,
+        "byteOffset": start_offset,
+        "totalLines": total_lines
+    }))
+}
@@ -4,12 +4,25 @@
 use tauri::Emitter;
 use regex::Regex;
 use lazy_static::lazy_static;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::{Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{SystemTime, Duration};
 
+use crate::modules::log_reader::archive;
+use crate::modules::log_reader::event_exporter;
+use crate::modules::log_reader::join_grace;
+use crate::modules::log_reader::log_reader;
+use crate::modules::log_reader::rejoin_storm;
+use crate::modules::log_reader::session_markers::{self, SessionMarkers};
+use crate::modules::log_reader::patterns::{PLAYER_JOIN_LEAVE_REGEX, USER_AUTHENTICATED_REGEX};
+use crate::modules::app_state::app_state;
+use crate::modules::settings::settings;
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone};
+
 // Get VRChat log directory (Windows: %LOCALAPPDATA%\..\LocalLow\VRChat\VRChat)
 fn default_vrchat_log_dir() -> PathBuf {
     let local_low = std::env::var("LOCALAPPDATA")
@@ -19,14 +32,7 @@ fn default_vrchat_log_dir() -> PathBuf {
     local_low.join("LocalLow").join("VRChat").join("VRChat")
 }
 
-// Regex pattern to match OnPlayerJoined/OnPlayerLeft events
-// Format: OnPlayerJoined <username> (usr_<uuid>)
-// Example: OnPlayerJoined Lamp? (usr_40f4043b-87e3-42c5-ba1f-ed0ad22e49e5)
 lazy_static! {
-    static ref PLAYER_JOIN_LEAVE_REGEX: Regex = Regex::new(
-        r"OnPlayer(Joined|Left)\s+(.+?)\s+\(usr_([a-f0-9-]+)\)"
-    ).expect("Failed to compile player join/leave regex");
-    
     // Regex pattern to match ban/warn events
     // Format: Admin "admin_name" (banned|warned) player "target_name" for the following reason: "reason"
     // Example: Admin "IceTiger540" banned player "IceTiger540" for the following reason: "Hateful Behavior"
@@ -45,12 +51,19 @@ lazy_static! {
         r"(?:^|\]\s+)(\d{4}\.\d{2}\.\d{2}\s+\d{2}:\d{2}:\d{2})"
     ).expect("Failed to compile timestamp regex");
 
-    // [Behaviour] Joining wrld_xxx:57420~private(...)~region(us) - extract world id and instance id (discard after first ~)
+    // [Behaviour] Joining wrld_xxx:57420~private(...)~region(us) - extract world id, instance id (up to first ~)
+    // and the remaining ~tag(...) suffix
     // Must match wrld_ to avoid incorrectly matching "Joining or Creating Room: X"
     static ref JOINING_WORLD_REGEX: Regex = Regex::new(
-        r"\[Behaviour\]\s+Joining\s+(wrld_[^:]+):([^~]+)"
+        r"\[Behaviour\]\s+Joining\s+(wrld_[^:]+):([^~\s]+)(\S*)"
     ).expect("Failed to compile joining world regex");
 
+    // A single ~name or ~name(value) tag from the instance string
+    // Example: ~group(grp_xxx)~groupAccessType(members)~region(eu)
+    static ref INSTANCE_TAG_REGEX: Regex = Regex::new(
+        r"~([A-Za-z]+)(?:\(([^)]*)\))?"
+    ).expect("Failed to compile instance tag regex");
+
     // [Behaviour] Joining or Creating Room: Furry Hideout
     static ref JOINING_ROOM_REGEX: Regex = Regex::new(
         r"\[Behaviour\]\s+Joining\s+or\s+Creating\s+Room:\s*(.+)"
@@ -63,10 +76,88 @@ struct LocationState {
     world_id: Option<String>,
     instance_id: Option<String>,
     room_name: Option<String>,
+    /// Access type parsed from the instance tags (see InstanceTags)
+    instance_type: Option<String>,
+    /// Owning group id for group instances
+    group_id: Option<String>,
+    /// Region from ~region(...) if present
+    region: Option<String>,
     /// Timestamp when we joined this instance (from Joining line). Used to discard moderation events within 15s.
     instance_joined_timestamp: Option<String>,
 }
 
+impl LocationState {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "world_id": self.world_id,
+            "instance_id": self.instance_id,
+            "room_name": self.room_name,
+            "instance_type": self.instance_type,
+            "group_id": self.group_id,
+            "region": self.region
+        })
+    }
+}
+
+/// Privacy/ownership details parsed from the ~tag suffix of an instance string
+#[derive(Default, Debug, Clone, PartialEq)]
+pub(crate) struct InstanceTags {
+    /// "public" | "friends+" | "friends" | "invite+" | "invite" | "group" | "group+" | "group_public"
+    /// None when the instance string carried no tags at all (older logs)
+    pub(crate) instance_type: Option<String>,
+    pub(crate) group_id: Option<String>,
+    pub(crate) region: Option<String>,
+}
+
+/// Parse ~tags from an instance string suffix, e.g. "~group(grp_x)~groupAccessType(members)~region(us)"
+fn parse_instance_tags(suffix: &str) -> InstanceTags {
+    let mut tags = InstanceTags::default();
+    let mut saw_any = false;
+    let mut private = false;
+    let mut can_request_invite = false;
+    let mut friends = false;
+    let mut hidden = false;
+    let mut group_access: Option<String> = None;
+
+    for cap in INSTANCE_TAG_REGEX.captures_iter(suffix) {
+        saw_any = true;
+        let name = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+        let value = cap.get(2).map(|m| m.as_str().trim().to_string()).filter(|v| !v.is_empty());
+        match name {
+            "private" => private = true,
+            "canRequestInvite" => can_request_invite = true,
+            "friends" => friends = true,
+            "hidden" => hidden = true,
+            "group" => tags.group_id = value,
+            "groupAccessType" => group_access = value,
+            "region" => tags.region = value,
+            _ => {}
+        }
+    }
+
+    if !saw_any {
+        return tags;
+    }
+
+    let instance_type = if tags.group_id.is_some() || group_access.is_some() {
+        match group_access.as_deref() {
+            Some("public") => "group_public",
+            Some("plus") => "group+",
+            _ => "group",
+        }
+    } else if private {
+        if can_request_invite { "invite+" } else { "invite" }
+    } else if friends {
+        "friends"
+    } else if hidden {
+        "friends+"
+    } else {
+        "public"
+    };
+    tags.instance_type = Some(instance_type.to_string());
+    tags
+}
+
 /// Instance history entry (join/leave) - in-memory, cleared on restart
 #[derive(Clone, serde::Serialize)]
 struct InstanceHistoryEntry {
@@ -75,6 +166,9 @@ struct InstanceHistoryEntry {
     world_id: Option<String>,
     instance_id: Option<String>,
     room_name: Option<String>,
+    instance_type: Option<String>,
+    group_id: Option<String>,
+    region: Option<String>,
 }
 
 const INSTANCE_HISTORY_MAX: usize = 200;
@@ -84,15 +178,69 @@ lazy_static! {
     static ref INSTANCE_HISTORY: Mutex<Vec<InstanceHistoryEntry>> = Mutex::new(Vec::new());
 }
 
+/// Wall-clock format VRChat uses for log line timestamps (no timezone information)
+pub const LOG_TS_FORMAT: &str = "%Y.%m.%d %H:%M:%S";
+
+lazy_static! {
+    /// Parsed log_timezone setting; the outer None means not loaded yet
+    static ref LOG_TIMEZONE: RwLock<Option<Option<chrono_tz::Tz>>> = RwLock::new(None);
+}
+
+/// (Re)load the log timezone from settings (after set_log_timezone or a backup restore)
+pub fn reload_log_timezone() {
+    let tz = settings::get_settings()
+        .ok()
+        .and_then(|s| s.log_timezone)
+        .and_then(|name| name.parse().ok());
+    if let Ok(mut cached) = LOG_TIMEZONE.write() {
+        *cached = Some(tz);
+    }
+}
+
+/// Timezone the logs were written in, from settings (None = system local)
+fn configured_log_timezone() -> Option<chrono_tz::Tz> {
+    if LOG_TIMEZONE.read().map(|cached| cached.is_none()).unwrap_or(false) {
+        reload_log_timezone();
+    }
+    LOG_TIMEZONE.read().ok().and_then(|cached| cached.flatten())
+}
+
+/// Interpret a log timestamp (YYYY.MM.DD HH:MM:SS) as an absolute point in time.
+/// Uses the configured log timezone, falling back to the system local timezone.
+/// All duration math on log timestamps should go through this so DST changes and
+/// logs copied from another machine are handled consistently.
+/// Ambiguous wall-clock times (DST fall-back) resolve to the earlier instant;
+/// times that don't exist (DST spring-forward gap) return None.
+pub fn parse_log_ts(ts: &str) -> Option<DateTime<FixedOffset>> {
+    let naive = NaiveDateTime::parse_from_str(ts.trim(), LOG_TS_FORMAT).ok()?;
+    match configured_log_timezone() {
+        Some(tz) => tz.from_local_datetime(&naive).earliest().map(|dt| dt.fixed_offset()),
+        None => chrono::Local.from_local_datetime(&naive).earliest().map(|dt| dt.fixed_offset()),
+    }
+}
+
+/// Format an absolute time as a log timestamp in the log timezone (inverse of parse_log_ts)
+pub fn format_log_ts(dt: &DateTime<FixedOffset>) -> String {
+    match configured_log_timezone() {
+        Some(tz) => dt.with_timezone(&tz).format(LOG_TS_FORMAT).to_string(),
+        None => dt.with_timezone(&chrono::Local).format(LOG_TS_FORMAT).to_string(),
+    }
+}
+
+/// Current time as a log timestamp (used when a line has no timestamp of its own)
+pub fn now_log_ts() -> String {
+    format_log_ts(&chrono::Utc::now().fixed_offset())
+}
+
 fn extract_timestamp_from_line(line: &str) -> String {
     TIMESTAMP_REGEX
         .captures(line)
         .and_then(|c| c.get(1))
         .map(|m| m.as_str().to_string())
-        .unwrap_or_else(|| chrono::Local::now().format("%Y.%m.%d %H:%M:%S").to_string())
+        .unwrap_or_else(now_log_ts)
 }
 
-fn push_instance_history_join(line: &str, world_id: Option<String>, instance_id: Option<String>, room_name: Option<String>) {
+fn push_instance_history_join(line: &str, world_id: Option<String>, instance_id: Option<String>, room_name: Option<String>, tags: InstanceTags) {
     if let Ok(mut hist) = INSTANCE_HISTORY.lock() {
         let timestamp = extract_timestamp_from_line(line);
         hist.push(InstanceHistoryEntry {
@@ -101,6 +249,9 @@ fn push_instance_history_join(line: &str, world_id: Option<String>, instance_id:
             world_id,
             instance_id,
             room_name,
+            instance_type: tags.instance_type,
+            group_id: tags.group_id,
+            region: tags.region,
         });
         let len = hist.len();
         if len > INSTANCE_HISTORY_MAX {
@@ -118,6 +269,9 @@ fn push_instance_history_leave(line: &str) {
             world_id: None,
             instance_id: None,
             room_name: None,
+            instance_type: None,
+            group_id: None,
+            region: None,
         });
         let len = hist.len();
         if len > INSTANCE_HISTORY_MAX {
@@ -139,134 +293,511 @@ fn update_last_history_room(room_name: Option<String>) {
 /// Get instance history (for stopwatch modal)
 #[tauri::command]
 pub fn get_instance_history() -> Result<Vec<serde_json::Value>, String> {
+    let labels = crate::modules::history::session_labels::load_labels().unwrap_or_default();
     if let Ok(hist) = INSTANCE_HISTORY.lock() {
         Ok(hist.iter().rev().map(|e| serde_json::json!({
             "timestamp": e.timestamp,
             "kind": e.kind,
+            "label": if e.kind == "join" { labels.get(&e.timestamp).cloned() } else { None },
             "world_id": e.world_id,
             "instance_id": e.instance_id,
-            "room_name": e.room_name
+            "room_name": e.room_name,
+            "instance_type": e.instance_type,
+            "group_id": e.group_id,
+            "region": e.region
         })).collect())
     } else {
         Err("Failed to get instance history".to_string())
     }
 }
 
-/// Parse player join/leave events from log lines
-/// Returns true if a join/leave event was found and emitted
-fn parse_player_join_leave(app_handle: &tauri::AppHandle, line: &str, file_name: &str) -> bool {
+/// What a single log line means to us. Produced by classify_line without touching any state,
+/// so the watcher and the backfill only decide which side effects to run.
+/// Serialized for external consumers by event_exporter::export_event.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ParsedEvent {
+    /// OnPlayerJoined / OnPlayerLeft
+    Player { joined: bool, username: String, user_id: String },
+    /// [Behaviour] Joining wrld_xxx:instance~tags
+    JoiningWorld { world_id: Option<String>, instance_id: Option<String>, tags: InstanceTags },
+    /// [Behaviour] Joining or Creating Room: Room Name
+    JoiningRoom { room_name: String },
+    /// [Behaviour] Successfully joined room
+    JoinedRoom,
+    /// [Behaviour] OnLeftRoom
+    LeftRoom,
+    /// Admin "x" banned|warned player "y" for the following reason: "z"
+    Moderation { admin: String, action: String, target: String, reason: String },
+    /// [Behaviour] User Authenticated: name (usr_xxx) - the local user
+    Authenticated { username: String, user_id: String },
+    Other,
+}
+
+impl ParsedEvent {
+    /// Event name used by the frontend ("player_joined" / "player_left") for player lines
+    pub(crate) fn player_event_kind(joined: bool) -> &'static str {
+        if joined { "player_joined" } else { "player_left" }
+    }
+}
+
+/// Classify a log line using the configured session markers
+fn classify_line(line: &str) -> ParsedEvent {
+    classify_line_with(line, &session_markers::current())
+}
+
+/// Classify a log line against the given session markers. Pure: no locks, no DB, no emits.
+fn classify_line_with(line: &str, markers: &SessionMarkers) -> ParsedEvent {
+    // Session boundaries come from configurable markers (see session_markers)
+    if markers.is_left_room(line) {
+        return ParsedEvent::LeftRoom;
+    }
+    if markers.is_joined_room(line) {
+        return ParsedEvent::JoinedRoom;
+    }
+
+    if let Some(captures) = JOINING_WORLD_REGEX.captures(line) {
+        let world_id = captures.get(1).map(|m| m.as_str().trim().to_string()).filter(|s| !s.is_empty());
+        let instance_id = captures.get(2).map(|m| m.as_str().trim().to_string()).filter(|s| !s.is_empty());
+        if world_id.is_some() || instance_id.is_some() {
+            let tags = parse_instance_tags(captures.get(3).map(|m| m.as_str()).unwrap_or(""));
+            return ParsedEvent::JoiningWorld { world_id, instance_id, tags };
+        }
+    }
+
+    if let Some(captures) = JOINING_ROOM_REGEX.captures(line) {
+        let room_name = captures.get(1).map(|m| m.as_str().trim()).unwrap_or("");
+        if !room_name.is_empty() {
+            return ParsedEvent::JoiningRoom { room_name: room_name.to_string() };
+        }
+    }
+
+    if let Some(captures) = MODERATION_EVENT_REGEX.captures(line) {
+        let action = captures.get(2).map(|m| m.as_str()).unwrap_or("");
+        return ParsedEvent::Moderation {
+            admin: captures.get(1).map(|m| m.as_str()).unwrap_or("").to_string(),
+            // Normalize action type: "banned" -> "ban", "warned" -> "warn"
+            action: if action == "warned" { "warn" } else { "ban" }.to_string(),
+            target: captures.get(3).map(|m| m.as_str()).unwrap_or("").to_string(),
+            reason: captures.get(4).map(|m| m.as_str()).unwrap_or("").to_string(),
+        };
+    }
+
+    if let Some(captures) = USER_AUTHENTICATED_REGEX.captures(line) {
+        return ParsedEvent::Authenticated {
+            username: captures.get(1).map(|m| m.as_str().trim()).unwrap_or("").to_string(),
+            user_id: captures.get(2).map(|m| m.as_str()).unwrap_or("").to_string(),
+        };
+    }
+
     if let Some(captures) = PLAYER_JOIN_LEAVE_REGEX.captures(line) {
-        let event_type = captures.get(1).map(|m| m.as_str()).unwrap_or("");
-        let username = captures.get(2).map(|m| m.as_str().trim()).unwrap_or("");
-        let user_id = captures.get(3).map(|m| m.as_str()).unwrap_or("");
-        
-        // Construct full user ID with usr_ prefix
-        let full_user_id = format!("usr_{}", user_id);
-        
-        // Determine event type
-        let event_kind = if event_type == "Joined" {
-            "player_joined"
-        } else {
-            "player_left"
+        return ParsedEvent::Player {
+            joined: captures.get(1).map(|m| m.as_str()) == Some("Joined"),
+            username: captures.get(2).map(|m| m.as_str().trim()).unwrap_or("").to_string(),
+            user_id: captures.get(3).map(|m| m.as_str()).unwrap_or("").to_string(),
         };
-        
-        // Emit structured event
-        let _ = app_handle.emit("player_event", serde_json::json!({
+    }
+
+    ParsedEvent::Other
+}
+
+/// Samples kept per event type by analyze_log_file
+const ANALYZE_SAMPLES_PER_KIND: usize = 5;
+
+/// Dry-run the parser over a log file: classify every line and return counts plus a few raw
+/// sample lines per event type. No DB writes, no emits, no location/history changes.
+/// Archived .txt.gz logs are decompressed transparently.
+#[tauri::command]
+pub fn analyze_log_file(path: String) -> Result<serde_json::Value, String> {
+    let bytes = archive::read_log_bytes(std::path::Path::new(&path))?;
+    let content = String::from_utf8_lossy(&bytes);
+
+    let kinds = ["player_joined", "player_left", "instance_change", "room_name", "joined_room", "left_room", "moderation", "authenticated"];
+    let mut counts: std::collections::HashMap<&str, usize> = kinds.iter().map(|k| (*k, 0)).collect();
+    let mut samples: std::collections::HashMap<&str, Vec<String>> = kinds.iter().map(|k| (*k, Vec::new())).collect();
+    let mut total_lines = 0usize;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        total_lines += 1;
+        let kind = match classify_line(trimmed) {
+            ParsedEvent::Player { joined, .. } => ParsedEvent::player_event_kind(joined),
+            ParsedEvent::JoiningWorld { .. } => "instance_change",
+            ParsedEvent::JoiningRoom { .. } => "room_name",
+            ParsedEvent::JoinedRoom => "joined_room",
+            ParsedEvent::LeftRoom => "left_room",
+            ParsedEvent::Moderation { .. } => "moderation",
+            ParsedEvent::Authenticated { .. } => "authenticated",
+            ParsedEvent::Other => continue,
+        };
+        *counts.entry(kind).or_insert(0) += 1;
+        let list = samples.entry(kind).or_default();
+        if list.len() < ANALYZE_SAMPLES_PER_KIND {
+            list.push(trimmed.to_string());
+        }
+    }
+
+    Ok(serde_json::json!({
+        "file": path,
+        "bytes": bytes.len(),
+        "lines": total_lines,
+        "counts": counts,
+        "samples": samples
+    }))
+}
+
+/// app_state key holding the local user's id (from the last "User Authenticated" line)
+pub const SELF_USER_ID_KEY: &str = "self_user_id";
+/// app_state key holding the local user's display name
+pub const SELF_USERNAME_KEY: &str = "self_username";
+
+/// Remember the authenticated (local) user so their own join/leave rows can be flagged
+fn record_authenticated_user(username: &str, user_id: &str) {
+    if is_injecting() {
+        return;
+    }
+    if let Err(e) = app_state::set(SELF_USER_ID_KEY, Some(user_id)) {
+        crate::debug_eprintln!("[log_parser] Failed to store self user id: {}", e);
+    }
+    let _ = app_state::set(SELF_USERNAME_KEY, Some(username));
+}
+
+/// True when user_id is the local (authenticated) user
+pub fn is_self_user(user_id: &str) -> bool {
+    app_state::get(SELF_USER_ID_KEY).map_or(false, |id| id == user_id)
+}
+
+/// When the UI opts in, player events are coalesced into player_events batches instead of
+/// one player_event each (a filling instance otherwise floods the webview)
+static PLAYER_EVENT_BATCHING: AtomicBool = AtomicBool::new(false);
+/// How long player events are collected before a batch is emitted
+const PLAYER_EVENT_BATCH_WINDOW: Duration = Duration::from_millis(200);
+
+lazy_static! {
+    static ref PENDING_PLAYER_EVENTS: Mutex<Vec<serde_json::Value>> = Mutex::new(Vec::new());
+}
+
+/// Opt in/out of batched player events (player_events { events: [...] })
+#[tauri::command]
+pub fn set_player_event_batching(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    PLAYER_EVENT_BATCHING.store(enabled, Ordering::SeqCst);
+    if !enabled {
+        flush_player_events(&app_handle);
+    }
+    Ok(())
+}
+
+/// Emit a player event payload directly, or queue it for the next batch
+fn send_player_event(app_handle: &tauri::AppHandle, payload: serde_json::Value) {
+    if !PLAYER_EVENT_BATCHING.load(Ordering::SeqCst) {
+        let _ = app_handle.emit("player_event", payload);
+        return;
+    }
+    let start_timer = match PENDING_PLAYER_EVENTS.lock() {
+        Ok(mut pending) => {
+            pending.push(payload);
+            pending.len() == 1
+        }
+        Err(_) => return,
+    };
+    // First event of a batch schedules the flush
+    if start_timer {
+        let app = app_handle.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(PLAYER_EVENT_BATCH_WINDOW);
+            flush_player_events(&app);
+        });
+    }
+}
+
+/// Emit any queued player events now (also called before instance_cleared so a batch
+/// from the previous instance never lands after the clear)
+fn flush_player_events(app_handle: &tauri::AppHandle) {
+    let events = match PENDING_PLAYER_EVENTS.lock() {
+        Ok(mut pending) => std::mem::take(&mut *pending),
+        Err(_) => return,
+    };
+    if !events.is_empty() {
+        let _ = app_handle.emit("player_events", serde_json::json!({ "events": events }));
+    }
+}
+
+lazy_static! {
+    /// Non-self users currently in the instance (user id -> (username, join log timestamp)),
+    /// tracked so we can tell when it goes empty
+    static ref ACTIVE_PLAYERS: Mutex<HashMap<String, (String, String)>> = Mutex::new(HashMap::new());
+    /// Roster captured at a Joining line (no OnLeftRoom seen), sent on the next instance_cleared
+    static ref DEPARTED_ROSTER: Mutex<Vec<serde_json::Value>> = Mutex::new(Vec::new());
+}
+
+/// User ids of the other players currently in the instance
+pub fn get_active_player_ids() -> Vec<String> {
+    ACTIVE_PLAYERS
+        .lock()
+        .map(|active| active.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Other players currently in the instance as { userId, username, joinedAt }, earliest join first
+pub fn get_active_players() -> Vec<serde_json::Value> {
+    let mut players: Vec<(String, String, String)> = ACTIVE_PLAYERS
+        .lock()
+        .map(|active| {
+            active
+                .iter()
+                .map(|(id, (name, ts))| (id.clone(), name.clone(), ts.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    players.sort_by(|a, b| a.2.cmp(&b.2));
+    players
+        .into_iter()
+        .map(|(id, name, ts)| serde_json::json!({ "userId": id, "username": name, "joinedAt": ts }))
+        .collect()
+}
+
+/// Forget the tracked roster (new instance session or backfill rebuild)
+fn clear_active_players() {
+    if let Ok(mut active) = ACTIVE_PLAYERS.lock() {
+        active.clear();
+    }
+}
+
+/// Roster stashed by a Joining line since the last instance_cleared (empty if none)
+fn take_departed_roster() -> Vec<serde_json::Value> {
+    DEPARTED_ROSTER
+        .lock()
+        .map(|mut roster| std::mem::take(&mut *roster))
+        .unwrap_or_default()
+}
+
+/// Capture who is still in the instance we're leaving and store it as a session roster.
+/// Must run while LOCATION_STATE still describes that instance.
+/// Returns the roster (earliest join first) so it can ride along on instance_cleared.
+fn snapshot_departing_roster(ended_at: &str) -> Vec<serde_json::Value> {
+    let roster = get_active_players();
+    if roster.is_empty() || is_injecting() {
+        return roster;
+    }
+    let context = LOCATION_STATE.lock().ok().and_then(|state| {
+        let instance_ts = state.instance_joined_timestamp.clone()?;
+        let location = match (&state.world_id, &state.instance_id) {
+            (Some(world), Some(instance)) => format!("{}:{}", world, instance),
+            _ => "N/A".to_string(),
+        };
+        Some((instance_ts, location, state.room_name.clone()))
+    });
+    if let Some((instance_ts, location, room_name)) = context {
+        if let Err(e) = crate::modules::history::session_rosters::record_roster(&instance_ts, &location, room_name.as_deref(), &roster, ended_at) {
+            crate::debug_eprintln!("[log_parser] Failed to store session roster: {}", e);
+        }
+    }
+    roster
+}
+
+/// Apply a join/leave to the tracked roster.
+/// Returns true only on the transition to empty (the last other player left).
+fn track_active_player(joined: bool, user_id: &str, username: &str, timestamp: &str) -> bool {
+    if user_id.is_empty() || is_self_user(user_id) {
+        return false;
+    }
+    let mut active = match ACTIVE_PLAYERS.lock() {
+        Ok(active) => active,
+        Err(_) => return false,
+    };
+    if joined {
+        active.insert(user_id.to_string(), (username.to_string(), timestamp.to_string()));
+        false
+    } else {
+        active.remove(user_id).is_some() && active.is_empty()
+    }
+}
+
+/// Record a join in the first-seen table; true when this join is the user's first sighting
+fn is_first_sighting(joined: bool, user_id: &str, username: &str, timestamp: &str) -> bool {
+    if !joined || user_id.is_empty() || is_injecting() {
+        return false;
+    }
+    crate::modules::history::first_seen::record_join(user_id, username, timestamp).unwrap_or_else(|e| {
+        crate::debug_eprintln!("[log_parser] Failed to record first sighting: {}", e);
+        false
+    })
+}
+
+/// Emit a player_event for a classified join/leave line, applying the join grace period.
+/// With grace on, joins are held back; a leave that arrives while its join is still held
+/// (and within the grace period by log time) drops both.
+fn emit_player_event(app_handle: &tauri::AppHandle, line: &str, file_name: &str, joined: bool, username: &str, user_id: &str) {
+    // Injected lines rely on a thread-local flag, so they never go through the sweeper task
+    if join_grace::grace_secs() == 0 || user_id.is_empty() || is_injecting() {
+        publish_player_event(app_handle, line, file_name, joined, username, user_id);
+        return;
+    }
+    
+    let log_ts = extract_timestamp_from_line(line);
+    // Joins that are past the grace period by this line's log time go out first
+    publish_expired_joins(app_handle, Some(&log_ts));
+    if joined {
+        join_grace::hold_join(user_id, username, &log_ts, line, file_name);
+        start_join_sweeper(app_handle);
+        return;
+    }
+    
+    if let Some(held) = join_grace::take_held_join(user_id) {
+        if join_grace::is_transient(&held.join_ts, &log_ts) {
+            crate::debug_println!("[log_parser] Collapsed transient join/leave for {} ({})", username, user_id);
+            return;
+        }
+        // Held join outlived the grace period by log time: publish it first
+        publish_player_event(app_handle, &held.line, &held.file_name, true, &held.username, &held.user_id);
+    }
+    publish_player_event(app_handle, line, file_name, joined, username, user_id);
+}
+
+/// Publish the held joins whose grace period is over
+fn publish_expired_joins(app_handle: &tauri::AppHandle, log_ts: Option<&str>) {
+    for held in join_grace::take_expired(log_ts) {
+        publish_player_event(app_handle, &held.line, &held.file_name, true, &held.username, &held.user_id);
+    }
+}
+
+/// Set while the join sweeper task is running
+static JOIN_SWEEPER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// One timer for all held joins: sleeps until the next one is due, publishes it, and exits
+/// once nothing is held. A no-op if the sweeper is already running.
+fn start_join_sweeper(app_handle: &tauri::AppHandle) {
+    if JOIN_SWEEPER_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Some(wait) = join_grace::next_deadline() {
+                tokio::time::sleep(wait).await;
+                publish_expired_joins(&app, None);
+                continue;
+            }
+            JOIN_SWEEPER_RUNNING.store(false, Ordering::SeqCst);
+            // A join held after the check above may have seen the flag still set: keep going
+            // for it unless another sweeper has already been started
+            if join_grace::next_deadline().is_none() || JOIN_SWEEPER_RUNNING.swap(true, Ordering::SeqCst) {
+                break;
+            }
+        }
+    });
+}
+
+/// Emit a player_event for a join/leave (roster, first-seen and rejoin tracking included)
+fn publish_player_event(app_handle: &tauri::AppHandle, line: &str, file_name: &str, joined: bool, username: &str, user_id: &str) {
+    let log_ts = extract_timestamp_from_line(line);
+    send_player_event(app_handle, serde_json::json!({
+        "file": file_name,
+        "event": ParsedEvent::player_event_kind(joined),
+        "username": username,
+        "user_id": user_id,
+        "is_self": is_self_user(user_id),
+        "first_seen": is_first_sighting(joined, user_id, username, &log_ts),
+        "timestamp": chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        "raw_line": line,
+        "injected": is_injecting()
+    }));
+    
+    // Same user joining over and over in a short window (crasher probing / ban evasion)
+    if joined && !user_id.is_empty() && !is_self_user(user_id) {
+        if let Some(count) = rejoin_storm::record_join(user_id) {
+            let _ = app_handle.emit("rejoin_storm", serde_json::json!({
+                "file": file_name,
+                "userId": user_id,
+                "username": username,
+                "count": count,
+                "windowSecs": rejoin_storm::window_secs(),
+                "timestamp": log_ts
+            }));
+            if rejoin_storm::should_alert(count) {
+                let body = format!("{} joined {} times in {}s", username, count, rejoin_storm::window_secs());
+                if let Err(e) = crate::modules::notifications::toast::show_toast(app_handle, "Rapid rejoins", &body, Some(user_id.to_string())) {
+                    crate::debug_eprintln!("[log_parser] Failed to show rejoin storm toast: {}", e);
+                }
+            }
+        }
+    }
+    
+    // Everyone else left: emit once on the transition (queued leaves go out first)
+    if track_active_player(joined, user_id, username, &log_ts) {
+        flush_player_events(app_handle);
+        let _ = app_handle.emit("instance_empty", serde_json::json!({
             "file": file_name,
-            "event": event_kind,
-            "username": username,
-            "user_id": full_user_id,
-            "timestamp": chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-            "raw_line": line
+            "timestamp": log_ts
         }));
-        
-        return true;
     }
-    
-    false
 }
 
 /// Minimum seconds in instance before recording moderation events (discard earlier)
 const MOD_LOG_MIN_SECONDS_IN_INSTANCE: i64 = 30;
 
-/// Parse ban/warn events from log lines
-/// Returns true if a moderation event was found and stored
-fn parse_ban_event(app_handle: &tauri::AppHandle, line: &str, _file_name: &str) -> bool {
-    if let Some(captures) = MODERATION_EVENT_REGEX.captures(line) {
-        let admin = captures.get(1).map(|m| m.as_str()).unwrap_or("").to_string();
-        let action_type = captures.get(2).map(|m| m.as_str()).unwrap_or("").to_string(); // "banned" or "warned"
-        let target = captures.get(3).map(|m| m.as_str()).unwrap_or("").to_string();
-        let reason = captures.get(4).map(|m| m.as_str()).unwrap_or("").to_string();
-        
-        // Normalize action type: "banned" -> "ban", "warned" -> "warn"
-        let action_normalized = if action_type == "warned" {
-            "warn".to_string()
-        } else {
-            "ban".to_string()
-        };
-        
-        // Extract timestamp from log line (format: YYYY.MM.DD HH:MM:SS)
-        // Looks for timestamp after the closing bracket of the filename
-        let timestamp = TIMESTAMP_REGEX
-            .captures(line)
-            .and_then(|c| c.get(1))
-            .map(|m| m.as_str().to_string())
-            .unwrap_or_else(|| chrono::Local::now().format("%Y.%m.%d %H:%M:%S").to_string());
-        
-        // Discard events within 15 seconds of joining the instance (avoids carryover/stale events)
-        if let Ok(state) = LOCATION_STATE.lock() {
-            if let Some(ref join_ts) = state.instance_joined_timestamp {
-                let join_dt = chrono::NaiveDateTime::parse_from_str(join_ts, "%Y.%m.%d %H:%M:%S");
-                let ban_dt = chrono::NaiveDateTime::parse_from_str(&timestamp, "%Y.%m.%d %H:%M:%S");
-                if let (Ok(join), Ok(ban)) = (join_dt, ban_dt) {
-                    let elapsed = ban.signed_duration_since(join);
-                    if elapsed >= chrono::Duration::zero()
-                        && elapsed < chrono::Duration::seconds(MOD_LOG_MIN_SECONDS_IN_INSTANCE)
-                    {
-                        crate::debug_println!(
-                            "[MOD_LOG] Discarding {} event (within {}s of instance join)",
-                            action_normalized,
-                            MOD_LOG_MIN_SECONDS_IN_INSTANCE
-                        );
-                        return false; // Don't record or emit
-                    }
+/// Store and emit a classified ban/warn line
+/// Returns true if the moderation event was stored (false when discarded by the join grace window)
+fn record_moderation_event(app_handle: &tauri::AppHandle, line: &str, admin: &str, action_normalized: &str, target: &str, reason: &str) -> bool {
+    // Extract timestamp from log line (format: YYYY.MM.DD HH:MM:SS)
+    // Looks for timestamp after the closing bracket of the filename
+    let timestamp = extract_timestamp_from_line(line);
+    
+    // Discard events within 15 seconds of joining the instance (avoids carryover/stale events)
+    if let Ok(state) = LOCATION_STATE.lock() {
+        if let Some(ref join_ts) = state.instance_joined_timestamp {
+            if let (Some(join), Some(ban)) = (parse_log_ts(join_ts), parse_log_ts(&timestamp)) {
+                let elapsed = ban.signed_duration_since(join);
+                if elapsed >= chrono::Duration::zero()
+                    && elapsed < chrono::Duration::seconds(MOD_LOG_MIN_SECONDS_IN_INSTANCE)
+                {
+                    crate::debug_println!(
+                        "[MOD_LOG] Discarding {} event (within {}s of instance join)",
+                        action_normalized,
+                        MOD_LOG_MIN_SECONDS_IN_INSTANCE
+                    );
+                    return false; // Don't record or emit
                 }
             }
         }
-        
-        // Store moderation log entry in database with extracted timestamp
-        let db_start = std::time::Instant::now();
-        let location = get_current_location_for_mod_log();
-        if let Err(e) = crate::modules::world_mod::world_mod_logs::add_ban_log(
-            admin.clone(),
-            target.clone(),
-            reason.clone(),
-            timestamp.clone(),
-            action_normalized.clone(),
-            location,
-        ) {
-            crate::debug_eprintln!("Failed to store moderation log: {}", e);
-        }
-        let db_duration = db_start.elapsed();
-        crate::debug_println!("[PERF] parse_ban_event DB store: {:.2}ms", db_duration.as_secs_f64() * 1000.0);
-        
-        // Emit event to frontend for real-time updates
-        let emit_start = std::time::Instant::now();
-        let _ = app_handle.emit("ban_event", serde_json::json!({
-            "admin": admin,
-            "target": target,
-            "reason": reason,
-            "timestamp": timestamp,
-            "action_type": action_normalized,
-            "raw_line": line
-        }));
-        let emit_duration = emit_start.elapsed();
-        crate::debug_println!("[PERF] parse_ban_event emit: {:.2}ms", emit_duration.as_secs_f64() * 1000.0);
-        
-        return true;
     }
     
-    false
+    // Store moderation log entry in database with extracted timestamp
+    // (injected lines are emitted only: never stored or exported to the API)
+    let db_start = std::time::Instant::now();
+    let location = get_current_location_for_mod_log();
+    if is_injecting() {
+        crate::debug_println!("[inject] Not storing simulated {} event", action_normalized);
+    } else if let Err(e) = crate::modules::world_mod::world_mod_logs::add_ban_log(
+        admin.to_string(),
+        target.to_string(),
+        reason.to_string(),
+        timestamp.clone(),
+        action_normalized.to_string(),
+        location,
+    ) {
+        crate::debug_eprintln!("Failed to store moderation log: {}", e);
+        crate::debug_error!(app_handle, "[log_parser] Failed to store moderation log: {}", e);
+    }
+    let db_duration = db_start.elapsed();
+    crate::debug_println!("[PERF] record_moderation_event DB store: {:.2}ms", db_duration.as_secs_f64() * 1000.0);
+    
+    // Emit event to frontend for real-time updates
+    let emit_start = std::time::Instant::now();
+    let _ = app_handle.emit("ban_event", serde_json::json!({
+        "admin": admin,
+        "target": target,
+        "reason": reason,
+        "timestamp": timestamp,
+        "action_type": action_normalized,
+        "raw_line": line
+    }));
+    let emit_duration = emit_start.elapsed();
+    crate::debug_println!("[PERF] record_moderation_event emit: {:.2}ms", emit_duration.as_secs_f64() * 1000.0);
+    
+    true
 }
 
 /// Clear location state (e.g. when instance is cleared)
@@ -291,105 +822,225 @@ pub fn get_current_location_for_mod_log() -> String {
     }
 }
 
+/// Log timestamp of the Joining line for the current instance (None when not in an instance)
+pub fn get_current_instance_timestamp() -> Option<String> {
+    LOCATION_STATE.lock().ok().and_then(|state| state.instance_joined_timestamp.clone())
+}
+
 /// Get current location (for frontend to request when tab becomes visible)
 #[tauri::command]
 pub fn get_current_location() -> Result<serde_json::Value, String> {
     if let Ok(state) = LOCATION_STATE.lock() {
-        Ok(serde_json::json!({
-            "world_id": state.world_id,
-            "instance_id": state.instance_id,
-            "room_name": state.room_name
-        }))
+        Ok(state.to_json())
     } else {
         Err("Failed to get location state".to_string())
     }
 }
 
-/// Parse [Behaviour] Joining world:instance and [Behaviour] Joining or Creating Room lines.
+/// Everything the UI needs on page load about where we are right now, in one call:
+/// the location fields (as in location_update), when we joined, the session label,
+/// and how many other players are currently present.
+#[tauri::command]
+pub fn get_current_instance() -> Result<serde_json::Value, String> {
+    let (mut current, joined_at) = LOCATION_STATE
+        .lock()
+        .map(|state| (state.to_json(), state.instance_joined_timestamp.clone()))
+        .map_err(|_| "Failed to get location state".to_string())?;
+    let label = joined_at.as_ref().and_then(|ts| {
+        crate::modules::history::session_labels::load_labels()
+            .ok()
+            .and_then(|labels| labels.get(ts).cloned())
+    });
+    current["in_instance"] = serde_json::json!(current["world_id"].is_string());
+    current["joined_at"] = serde_json::json!(joined_at);
+    current["label"] = serde_json::json!(label);
+    current["active_user_count"] = serde_json::json!(get_active_player_ids().len());
+    Ok(current)
+}
+
+/// Apply classified Joining world:instance / Joining or Creating Room lines to the location state.
 /// Updates in-memory state (latest only). If emit is true, emits location_update event.
-fn parse_location_update(app_handle: &tauri::AppHandle, line: &str, emit: bool) -> bool {
+fn apply_location_event(app_handle: &tauri::AppHandle, line: &str, event: &ParsedEvent, emit: bool) -> bool {
     let mut updated = false;
 
-    // [Behaviour] Joining wrld_xxx:57420~...
-    if let Some(captures) = JOINING_WORLD_REGEX.captures(line) {
-        let world_id = captures.get(1).map(|m| m.as_str().trim().to_string()).filter(|s| !s.is_empty());
-        let instance_id = captures.get(2).map(|m| m.as_str().trim().to_string()).filter(|s| !s.is_empty());
-
-        if world_id.is_some() || instance_id.is_some() {
-            push_instance_history_join(line, world_id.clone(), instance_id.clone(), None);
+    match event {
+        // [Behaviour] Joining wrld_xxx:57420~...
+        ParsedEvent::JoiningWorld { world_id, instance_id, tags } => {
+            // OnLeftRoom was missed (re-joining): the old roster is still tracked, so store it
+            // now while the location still belongs to the instance it was collected in
+            if emit && !get_active_player_ids().is_empty() {
+                let roster = snapshot_departing_roster(&extract_timestamp_from_line(line));
+                if let Ok(mut departed) = DEPARTED_ROSTER.lock() {
+                    *departed = roster;
+                }
+            }
+            push_instance_history_join(line, world_id.clone(), instance_id.clone(), None, tags.clone());
             if let Ok(mut state) = LOCATION_STATE.lock() {
                 if world_id.is_some() {
-                    state.world_id = world_id;
+                    state.world_id = world_id.clone();
                 }
                 if instance_id.is_some() {
-                    state.instance_id = instance_id;
+                    state.instance_id = instance_id.clone();
                 }
+                state.instance_type = tags.instance_type.clone();
+                state.group_id = tags.group_id.clone();
+                state.region = tags.region.clone();
                 state.instance_joined_timestamp = Some(extract_timestamp_from_line(line));
                 updated = true;
             }
+            match world_id.as_deref() {
+                Some(world) if !is_injecting() => {
+                    let result = crate::modules::history::instance_visits::record_visit(
+                        &extract_timestamp_from_line(line),
+                        world,
+                        instance_id.as_deref(),
+                        tags.region.as_deref(),
+                        tags.instance_type.as_deref(),
+                        tags.group_id.as_deref(),
+                    );
+                    if let Err(e) = result {
+                        crate::debug_eprintln!("[log_parser] Failed to record instance visit: {}", e);
+                    }
+                }
+                _ => {}
+            }
         }
-    }
-
-    // [Behaviour] Joining or Creating Room: Room Name
-    if let Some(captures) = JOINING_ROOM_REGEX.captures(line) {
-        let room_name = captures.get(1).map(|m| m.as_str().trim().to_string()).filter(|s| !s.is_empty());
-
-        if room_name.is_some() {
-            update_last_history_room(room_name.clone());
+        // [Behaviour] Joining or Creating Room: Room Name
+        ParsedEvent::JoiningRoom { room_name } => {
+            update_last_history_room(Some(room_name.clone()));
+            let mut instance_ts = None;
             if let Ok(mut state) = LOCATION_STATE.lock() {
-                state.room_name = room_name;
+                state.room_name = Some(room_name.clone());
+                instance_ts = state.instance_joined_timestamp.clone();
                 updated = true;
             }
+            if let Some(ts) = instance_ts.filter(|_| !is_injecting()) {
+                if let Err(e) = crate::modules::history::instance_visits::set_room_name(&ts, room_name) {
+                    crate::debug_eprintln!("[log_parser] Failed to record room name: {}", e);
+                }
+            }
         }
+        _ => {}
     }
 
     if updated && emit {
         if let Ok(state) = LOCATION_STATE.lock() {
-            let _ = app_handle.emit("location_update", serde_json::json!({
-                "world_id": state.world_id,
-                "instance_id": state.instance_id,
-                "room_name": state.room_name
-            }));
+            let _ = app_handle.emit("location_update", state.to_json());
         }
     }
 
     updated
 }
 
-pub fn emit_log_line(app_handle: &tauri::AppHandle, line: &str, file_name: &str) {
-    // Check for "[Behaviour] Successfully joined room" or "[Behaviour] OnLeftRoom"
-    // These indicate a new instance session or leaving the instance
-    if line.contains("[Behaviour] Successfully joined room") || 
-       line.contains("[Behaviour] OnLeftRoom") {
-        // Only clear location when leaving - "Successfully joined room" comes AFTER Joining lines,
-        // so clearing here would wipe the location we just parsed for the new instance
-        let left = line.contains("[Behaviour] OnLeftRoom");
-        if left {
-            push_instance_history_leave(line);
-            clear_location_state();
-            let _ = app_handle.emit("location_update", serde_json::json!({
-                "world_id": null,
-                "instance_id": null,
-                "room_name": null
-            }));
-        }
-        // Emit event to clear instance monitor (clears player list)
-        // left: true when OnLeftRoom so frontend can clear location/timer; false when Successfully joined room
-        let _ = app_handle.emit("instance_cleared", serde_json::json!({
-            "file": file_name,
-            "timestamp": chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-            "left": left
-        }));
+thread_local! {
+    /// Set while inject_log_lines runs on this thread. The real reader thread is unaffected.
+    static INJECTING: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// True while processing simulated lines: persistence (ban log + API export, first-seen,
+/// self user) is skipped and player_events carry injected: true so the UI skips its side effects
+fn is_injecting() -> bool {
+    INJECTING.with(|flag| flag.get())
+}
+
+/// Holds the INJECTING flag for its lifetime, so a panic mid-injection can't leave it set
+struct InjectingGuard;
+
+impl InjectingGuard {
+    fn new() -> Self {
+        INJECTING.with(|flag| flag.set(true));
+        InjectingGuard
     }
+}
+
+impl Drop for InjectingGuard {
+    fn drop(&mut self) {
+        INJECTING.with(|flag| flag.set(false));
+    }
+}
 
-    // Parse location lines ([Behaviour] Joining world:instance, Joining or Creating Room)
-    parse_location_update(app_handle, line, true);
+/// Feed lines through the same pipeline as the log watcher (debug builds only).
+/// Injected lines replace the in-memory location/roster state, so this refuses to run while
+/// the log watcher is active. Nothing is written to the databases.
+/// Returns the number of lines processed.
+#[tauri::command]
+pub fn inject_log_lines(app_handle: tauri::AppHandle, lines: Vec<String>, file_name: Option<String>) -> Result<usize, String> {
+    if !cfg!(debug_assertions) {
+        return Err("inject_log_lines is only available in debug builds".to_string());
+    }
+    if log_reader::is_log_reader_running() {
+        return Err("Stop the log watcher before injecting lines".to_string());
+    }
+    let file_name = file_name.unwrap_or_else(|| "injected".to_string());
     
-    // Check for ban events
-    parse_ban_event(app_handle, line, file_name);
+    let _injecting = InjectingGuard::new();
+    let mut processed = 0;
+    for line in lines.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        emit_log_line(&app_handle, line, &file_name);
+        processed += 1;
+    }
+    flush_player_events(&app_handle);
     
-    // Check for player join/leave events
-    parse_player_join_leave(app_handle, line, file_name);
+    Ok(processed)
+}
+
+pub fn emit_log_line(app_handle: &tauri::AppHandle, line: &str, file_name: &str) {
+    let event = classify_line(line);
+    
+    // Machine-readable feed for external tooling (injected lines aren't real events)
+    if !is_injecting() {
+        event_exporter::record(&event, &extract_timestamp_from_line(line), file_name);
+    }
+    
+    match event {
+        // "[Behaviour] Successfully joined room" / "[Behaviour] OnLeftRoom" indicate a new
+        // instance session or leaving the instance
+        event @ (ParsedEvent::JoinedRoom | ParsedEvent::LeftRoom) => {
+            // Only clear location when leaving - "Successfully joined room" comes AFTER Joining lines,
+            // so clearing here would wipe the location we just parsed for the new instance
+            let left = event == ParsedEvent::LeftRoom;
+            // Leaving: snapshot the roster before the location/roster are cleared.
+            // Joined: the location already points at the new instance, so only pass on what
+            // the Joining line captured
+            let previous_roster = if left {
+                take_departed_roster();
+                snapshot_departing_roster(&extract_timestamp_from_line(line))
+            } else {
+                take_departed_roster()
+            };
+            clear_active_players();
+            rejoin_storm::clear();
+            join_grace::clear();
+            if left {
+                push_instance_history_leave(line);
+                clear_location_state();
+                let _ = app_handle.emit("location_update", LocationState::default().to_json());
+            }
+            // Emit event to clear instance monitor (clears player list)
+            flush_player_events(app_handle);
+            // left: true when OnLeftRoom so frontend can clear location/timer; false when Successfully joined room
+            let _ = app_handle.emit("instance_cleared", serde_json::json!({
+                "file": file_name,
+                "timestamp": chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                "left": left,
+                "previousRoster": previous_roster
+            }));
+        }
+        // Location lines ([Behaviour] Joining world:instance, Joining or Creating Room)
+        event @ (ParsedEvent::JoiningWorld { .. } | ParsedEvent::JoiningRoom { .. }) => {
+            apply_location_event(app_handle, line, &event, true);
+        }
+        ParsedEvent::Moderation { admin, action, target, reason } => {
+            record_moderation_event(app_handle, line, &admin, &action, &target, &reason);
+        }
+        ParsedEvent::Player { joined, username, user_id } => {
+            emit_player_event(app_handle, line, file_name, joined, &username, &user_id);
+        }
+        ParsedEvent::Authenticated { username, user_id } => {
+            record_authenticated_user(&username, &user_id);
+        }
+        ParsedEvent::Other => {}
+    }
     
     // Always emit the raw log line event to frontend
     let _ = app_handle.emit("log_line", serde_json::json!({
@@ -399,6 +1050,11 @@ pub fn emit_log_line(app_handle: &tauri::AppHandle, line: &str, file_name: &str)
     }));
 }
 
+/// Most recently modified output_log_*.txt in the VRChat log directory (if any)
+pub fn find_latest_log_file() -> Result<Option<PathBuf>, String> {
+    find_most_recently_modified_log_file(&default_vrchat_log_dir())
+}
+
 /// Find the log file with the most recent modification time
 fn find_most_recently_modified_log_file(log_dir: &PathBuf) -> Result<Option<PathBuf>, String> {
     let entries = std::fs::read_dir(log_dir)
@@ -439,6 +1095,59 @@ fn find_most_recently_modified_log_file(log_dir: &PathBuf) -> Result<Option<Path
     Ok(most_recent_file.map(|(path, _)| path))
 }
 
+/// Last "User Authenticated" (username, user id) in a single log file
+fn find_authenticated_user_in_file(path: &PathBuf) -> Option<(String, String)> {
+    let bytes = std::fs::read(path).ok()?;
+    let content = String::from_utf8_lossy(&bytes);
+    let captures = USER_AUTHENTICATED_REGEX.captures_iter(&content).last()?;
+    Some((
+        captures.get(1).map(|m| m.as_str().trim()).unwrap_or("").to_string(),
+        captures.get(2).map(|m| m.as_str()).unwrap_or("").to_string(),
+    ))
+}
+
+/// Find the most recent authenticated user across all logs (newest log by modification time first)
+/// and store it as the self user. Falls back to the last stored value when no log has one.
+pub fn refresh_authenticated_user() -> Option<(String, String)> {
+    let mut logs: Vec<(PathBuf, SystemTime)> = std::fs::read_dir(default_vrchat_log_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| {
+                    e.file_name()
+                        .to_str()
+                        .map(|n| n.starts_with("output_log_") && n.ends_with(".txt"))
+                        .unwrap_or(false)
+                })
+                .filter_map(|e| Some((e.path(), e.metadata().ok()?.modified().ok()?)))
+                .collect()
+        })
+        .unwrap_or_default();
+    logs.sort_by(|a, b| b.1.cmp(&a.1));
+
+    for (path, _) in logs {
+        if let Some((username, user_id)) = find_authenticated_user_in_file(&path) {
+            record_authenticated_user(&username, &user_id);
+            return Some((username, user_id));
+        }
+    }
+
+    let user_id = app_state::get(SELF_USER_ID_KEY)?;
+    Some((app_state::get(SELF_USERNAME_KEY).unwrap_or_default(), user_id))
+}
+
+/// Get the local (authenticated) VRChat user as { username, userId }, or null if unknown
+#[tauri::command]
+pub fn get_authenticated_user() -> Result<serde_json::Value, String> {
+    Ok(match refresh_authenticated_user() {
+        Some((username, user_id)) => serde_json::json!({
+            "username": username,
+            "userId": user_id
+        }),
+        None => serde_json::Value::Null,
+    })
+}
+
 // Event cache for retroactive scan
 #[derive(Debug, Clone)]
 struct CachedPlayerEvent {
@@ -449,14 +1158,77 @@ struct CachedPlayerEvent {
     raw_line: String,
 }
 
+/// Remove joins whose matching leave falls within the join grace period, and those leaves.
+/// Expects events sorted oldest first.
+fn collapse_transient_events(events: Vec<CachedPlayerEvent>) -> Vec<CachedPlayerEvent> {
+    let mut open_joins: HashMap<&str, usize> = HashMap::new();
+    let mut dropped = vec![false; events.len()];
+    for (i, event) in events.iter().enumerate() {
+        if event.event_type == "player_joined" {
+            open_joins.insert(event.user_id.as_str(), i);
+        } else if let Some(j) = open_joins.remove(event.user_id.as_str()) {
+            if join_grace::is_transient(&events[j].timestamp, &event.timestamp) {
+                dropped[j] = true;
+                dropped[i] = true;
+            }
+        }
+    }
+    events
+        .into_iter()
+        .zip(dropped)
+        .filter(|(_, drop)| !drop)
+        .map(|(event, _)| event)
+        .collect()
+}
+
+/// Default size of the log tail the backfill searches for the instance anchor first
+const BACKFILL_SCAN_DEFAULT_BYTES: u64 = 4 * 1024 * 1024;
+const BACKFILL_SCAN_MIN_BYTES: u64 = 64 * 1024;
+
+/// Outcome of replaying the current instance from the latest log
+struct BackfillSummary {
+    file_name: String,
+    joins: usize,
+    leaves: usize,
+    anchor_found: bool, // False if no Joining line was found and the whole file was replayed
+    empty: bool,        // The log file had no content
+}
+
 /// Retroactive scan: Scan the latest log file from bottom up for join/leave events
 /// Scans from bottom until "[Behaviour] Successfully joined room" marker or top of file
 /// Only processes if file was modified within last 10 minutes
 /// Caches all events and emits them in chronological order (oldest first)
 #[tauri::command]
 pub fn manual_refresh_scan(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let summary = backfill_from_latest_log(&app_handle)?;
+    if summary.empty {
+        return Ok("Log file is empty".to_string());
+    }
+    Ok(format!(
+        "Scanned log file: found {} joins and {} leaves",
+        summary.joins, summary.leaves
+    ))
+}
+
+/// Rebuild the current instance roster from the latest log (same replay as manual_refresh_scan),
+/// returning how many joins/leaves were reconstructed
+#[tauri::command]
+pub fn backfill_current_instance(app_handle: tauri::AppHandle) -> Result<serde_json::Value, String> {
+    let summary = backfill_from_latest_log(&app_handle)?;
+    Ok(serde_json::json!({
+        "file": summary.file_name,
+        "joins": summary.joins,
+        "leaves": summary.leaves,
+        "anchorFound": summary.anchor_found
+    }))
+}
+
+/// Find the latest log, locate the last "Joining wrld_" anchor and replay everything after it:
+/// location state is rebuilt, moderation events are recorded and player events are re-emitted
+/// in chronological order.
+fn backfill_from_latest_log(app_handle: &tauri::AppHandle) -> Result<BackfillSummary, String> {
     let start_time = std::time::Instant::now();
-    crate::debug_println!("[PERF] manual_refresh_scan START");
+    crate::debug_println!("[PERF] backfill START");
     
     // Use the default VRChat log directory
     let log_dir = default_vrchat_log_dir();
@@ -468,7 +1240,7 @@ pub fn manual_refresh_scan(app_handle: tauri::AppHandle) -> Result<String, Strin
         None => return Err("No log files found".to_string()),
     };
     let find_duration = find_start.elapsed();
-    crate::debug_println!("[PERF] manual_refresh_scan find_most_recently_modified_log_file: {:.2}ms", find_duration.as_secs_f64() * 1000.0);
+    crate::debug_println!("[PERF] backfill find_most_recently_modified_log_file: {:.2}ms", find_duration.as_secs_f64() * 1000.0);
     
     // Check if file exists
     if !log_file_path.exists() {
@@ -497,7 +1269,7 @@ pub fn manual_refresh_scan(app_handle: tauri::AppHandle) -> Result<String, Strin
         return Err("Failed to calculate file age".to_string());
     }
     let metadata_duration = metadata_start.elapsed();
-    crate::debug_println!("[PERF] manual_refresh_scan metadata check: {:.2}ms", metadata_duration.as_secs_f64() * 1000.0);
+    crate::debug_println!("[PERF] backfill metadata check: {:.2}ms", metadata_duration.as_secs_f64() * 1000.0);
     
     // Get file name for event emission
     let file_name = log_file_path.file_name()
@@ -510,99 +1282,108 @@ pub fn manual_refresh_scan(app_handle: tauri::AppHandle) -> Result<String, Strin
     let mut file = File::open(&log_file_path)
         .map_err(|e| format!("Failed to open log file: {}", e))?;
     let open_duration = open_start.elapsed();
-    crate::debug_println!("[PERF] manual_refresh_scan file open: {:.2}ms", open_duration.as_secs_f64() * 1000.0);
+    crate::debug_println!("[PERF] backfill file open: {:.2}ms", open_duration.as_secs_f64() * 1000.0);
     
     let file_size = metadata.len();
     if file_size == 0 {
-        return Ok("Log file is empty".to_string());
+        return Ok(BackfillSummary {
+            file_name,
+            joins: 0,
+            leaves: 0,
+            anchor_found: false,
+            empty: true,
+        });
     }
-    crate::debug_println!("[PERF] manual_refresh_scan file size: {} bytes", file_size);
+    crate::debug_println!("[PERF] backfill file size: {} bytes", file_size);
     
-    // Read file from bottom up in chunks
-    const CHUNK_SIZE: u64 = 8192; // 8KB chunks
-    let mut buffer = Vec::new();
-    let mut position = file_size;
+    // Read the tail of the file and look for the last "[Behaviour] Joining wrld_xxx:instance"
+    // (earliest of the three instance lines; always occurs first). If the anchor isn't in the
+    // window, double the window until it is found or the whole file has been read.
+    let mut window = settings::get_settings()
+        .ok()
+        .and_then(|s| s.backfill_scan_bytes)
+        .unwrap_or(BACKFILL_SCAN_DEFAULT_BYTES)
+        .max(BACKFILL_SCAN_MIN_BYTES)
+        .min(file_size);
+    let mut buffer: Vec<u8>;
     let mut found_joining = false;
     
-    // Read backwards in chunks
-    while position > 0 && !found_joining {
-        let chunk_start = if position > CHUNK_SIZE {
-            position - CHUNK_SIZE
-        } else {
-            0
-        };
-        
-        let chunk_size = (position - chunk_start) as usize;
-        
-        file.seek(SeekFrom::Start(chunk_start))
+    loop {
+        let window_start = file_size - window;
+        file.seek(SeekFrom::Start(window_start))
             .map_err(|e| format!("Failed to seek in file: {}", e))?;
+        buffer = Vec::with_capacity(window as usize);
+        (&mut file).take(window).read_to_end(&mut buffer)
+            .map_err(|e| format!("Failed to read log tail: {}", e))?;
         
-        let mut chunk = vec![0u8; chunk_size];
-        let bytes_read = file.read(&mut chunk)
-            .map_err(|e| format!("Failed to read chunk: {}", e))?;
+        let buffer_str = String::from_utf8_lossy(&buffer).to_string();
         
-        if bytes_read == 0 {
-            break;
-        }
-        
-        // Prepend chunk to buffer (only the bytes we actually read)
-        buffer.splice(0..0, chunk[..bytes_read].iter().cloned());
-        
-        // Check for "[Behaviour] Joining wrld_xxx:instance" in the buffer (earliest of the three; occurs first)
-        let buffer_str = match String::from_utf8(buffer.clone()) {
-            Ok(s) => s,
-            Err(_) => {
-                // If UTF-8 conversion fails, try with lossy conversion
-                String::from_utf8_lossy(&buffer).to_string()
-            }
-        };
-        
-        if buffer_str.contains("Joining wrld_") {
-            found_joining = true;
-
-            // Find the last occurrence and keep from the start of that line (includes Joining, Joining or Creating Room, Successfully joined room, player events)
-            if let Some(last_pos) = buffer_str.rfind("Joining wrld_") {
-                // Find the start of the line containing the marker (previous newline or start of buffer)
-                let line_start = buffer_str[..last_pos].rfind('\n')
-                    .map(|i| i + 1)
-                    .unwrap_or(0);
-                let remaining_str = &buffer_str[line_start..];
-                buffer = remaining_str.as_bytes().to_vec();
+        // Find the last occurrence and keep from the start of that line (includes Joining, Joining or Creating Room, Successfully joined room, player events)
+        if let Some(last_pos) = buffer_str.rfind("Joining wrld_") {
+            // Find the start of the line containing the marker (previous newline or start of buffer)
+            let newline = buffer_str[..last_pos].rfind('\n');
+            // If the anchor line is cut off by the window start, widen the window once more
+            if newline.is_some() || window_start == 0 {
+                let line_start = newline.map(|i| i + 1).unwrap_or(0);
+                buffer = buffer_str[line_start..].as_bytes().to_vec();
+                found_joining = true;
+                
+                let anchor_distance = file_size - window_start - line_start as u64;
+                crate::debug_info!(
+                    app_handle,
+                    "[backfill] Instance anchor found {} KB before the end of {} (scan window {} KB)",
+                    anchor_distance / 1024,
+                    file_name,
+                    window / 1024
+                );
+                
+                // Emit event to clear instance monitor before we push new data
+                flush_player_events(app_handle);
+                let _ = app_handle.emit("instance_cleared", serde_json::json!({
+                    "file": file_name,
+                    "timestamp": chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+                }));
+                break;
             }
-
-            // Emit event to clear instance monitor before we push new data
-            let _ = app_handle.emit("instance_cleared", serde_json::json!({
-                "file": file_name,
-                "timestamp": chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
-            }));
         }
         
-        position = chunk_start;
+        if window_start == 0 {
+            crate::debug_info!(
+                app_handle,
+                "[backfill] No instance anchor in {} ({} KB), replaying the whole file",
+                file_name,
+                file_size / 1024
+            );
+            break;
+        }
+        window = window.saturating_mul(2).min(file_size);
     }
     
     // If we didn't find the marker, process the entire file
     // Parse all lines in buffer (from bottom to top)
     let parse_start = std::time::Instant::now();
     let content = String::from_utf8_lossy(&buffer);
-    let all_lines: Vec<&str> = content.lines().collect();
+    let markers = session_markers::current();
+    let all_lines: Vec<(&str, ParsedEvent)> = content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| (line, classify_line_with(line, &markers)))
+        .collect();
     let parse_duration = parse_start.elapsed();
-    crate::debug_println!("[PERF] manual_refresh_scan parsed {} lines: {:.2}ms", all_lines.len(), parse_duration.as_secs_f64() * 1000.0);
+    crate::debug_println!("[PERF] backfill parsed {} lines: {:.2}ms", all_lines.len(), parse_duration.as_secs_f64() * 1000.0);
 
     // Parse location from lines (forward order so last match wins - most recent state)
     clear_location_state();
-    for line in all_lines.iter() {
-        let trimmed = line.trim();
-        if !trimmed.is_empty() {
-            parse_location_update(&app_handle, trimmed, false);
+    for (line, event) in all_lines.iter() {
+        if let ParsedEvent::Authenticated { username, user_id } = event {
+            record_authenticated_user(username, user_id);
         }
+        apply_location_event(app_handle, line, event, false);
     }
     // Emit location_update once with final state
     if let Ok(state) = LOCATION_STATE.lock() {
-        let _ = app_handle.emit("location_update", serde_json::json!({
-            "world_id": state.world_id,
-            "instance_id": state.instance_id,
-            "room_name": state.room_name
-        }));
+        let _ = app_handle.emit("location_update", state.to_json());
     }
 
     // Cache all player events during scan (don't emit yet)
@@ -612,86 +1393,240 @@ pub fn manual_refresh_scan(app_handle: tauri::AppHandle) -> Result<String, Strin
     
     // Process lines in reverse order (from newest to oldest) to collect events
     let process_start = std::time::Instant::now();
-    for line in all_lines.iter().rev() {
-        let trimmed = line.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
-        
-        // Parse the line for ban events (these can be emitted immediately)
-        parse_ban_event(&app_handle, trimmed, &file_name);
-        
-        // Parse the line for join/leave events and cache them
-        if let Some(captures) = PLAYER_JOIN_LEAVE_REGEX.captures(trimmed) {
-            let event_type = captures.get(1).map(|m| m.as_str()).unwrap_or("");
-            let username = captures.get(2).map(|m| m.as_str().trim()).unwrap_or("");
-            let user_id = captures.get(3).map(|m| m.as_str()).unwrap_or("");
-            
-            // Construct full user ID with usr_ prefix
-            let full_user_id = format!("usr_{}", user_id);
-            
-            // Determine event type
-            let event_kind = if event_type == "Joined" {
-                "player_joined"
-            } else {
-                "player_left"
-            };
-            
-            // Extract timestamp from log line
-            let timestamp = TIMESTAMP_REGEX
-                .captures(trimmed)
-                .and_then(|c| c.get(1))
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_else(|| chrono::Local::now().format("%Y.%m.%d %H:%M:%S").to_string());
-            
-            // Cache the event
-            cached_events.push(CachedPlayerEvent {
-                event_type: event_kind.to_string(),
-                username: username.to_string(),
-                user_id: full_user_id,
-                timestamp: timestamp.clone(),
-                raw_line: trimmed.to_string(),
-            });
-            
-            if event_kind == "player_joined" {
-                join_count += 1;
-            } else {
-                leave_count += 1;
+    for (line, event) in all_lines.iter().rev() {
+        match event {
+            // Ban events can be stored/emitted immediately
+            ParsedEvent::Moderation { admin, action, target, reason } => {
+                record_moderation_event(app_handle, line, admin, action, target, reason);
             }
+            // Join/leave events are cached and emitted in order below
+            ParsedEvent::Player { joined, username, user_id } => {
+                cached_events.push(CachedPlayerEvent {
+                    event_type: ParsedEvent::player_event_kind(*joined).to_string(),
+                    username: username.clone(),
+                    user_id: user_id.clone(),
+                    timestamp: extract_timestamp_from_line(line),
+                    raw_line: line.to_string(),
+                });
+                
+                if *joined {
+                    join_count += 1;
+                } else {
+                    leave_count += 1;
+                }
+            }
+            _ => {}
         }
     }
     let process_duration = process_start.elapsed();
-    crate::debug_println!("[PERF] manual_refresh_scan process lines: {:.2}ms ({} joins, {} leaves)", process_duration.as_secs_f64() * 1000.0, join_count, leave_count);
+    crate::debug_println!("[PERF] backfill process lines: {:.2}ms ({} joins, {} leaves)", process_duration.as_secs_f64() * 1000.0, join_count, leave_count);
     
     // Sort cached events by timestamp (oldest first) so joins happen before leaves
     let sort_start = std::time::Instant::now();
     cached_events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
     let sort_duration = sort_start.elapsed();
-    crate::debug_println!("[PERF] manual_refresh_scan sort events: {:.2}ms", sort_duration.as_secs_f64() * 1000.0);
+    crate::debug_println!("[PERF] backfill sort events: {:.2}ms", sort_duration.as_secs_f64() * 1000.0);
+    
+    // Drop join/leave pairs shorter than the join grace period (no-op when it's off)
+    if join_grace::grace_secs() > 0 {
+        cached_events = collapse_transient_events(cached_events);
+    }
     
     // Capture event count before moving cached_events
     let event_count = cached_events.len();
     
-    // Now emit all events in chronological order
+    // Now emit all events in chronological order (rebuilding the tracked roster as we go)
     let emit_start = std::time::Instant::now();
+    clear_active_players();
     for event in cached_events {
-        let _ = app_handle.emit("player_event", serde_json::json!({
+        track_active_player(event.event_type == "player_joined", &event.user_id, &event.username, &event.timestamp);
+        send_player_event(app_handle, serde_json::json!({
             "file": file_name,
             "event": event.event_type,
             "username": event.username,
+            "is_self": is_self_user(&event.user_id),
+            "first_seen": is_first_sighting(event.event_type == "player_joined", &event.user_id, &event.username, &event.timestamp),
             "user_id": event.user_id,
             "timestamp": chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
             "raw_line": event.raw_line
         }));
     }
     let emit_duration = emit_start.elapsed();
-    crate::debug_println!("[PERF] manual_refresh_scan emit events: {:.2}ms ({} events)", emit_duration.as_secs_f64() * 1000.0, event_count);
+    crate::debug_println!("[PERF] backfill emit events: {:.2}ms ({} events)", emit_duration.as_secs_f64() * 1000.0, event_count);
     
     let total_duration = start_time.elapsed();
-    crate::debug_println!("[PERF] manual_refresh_scan END: {:.2}ms", total_duration.as_secs_f64() * 1000.0);
+    crate::debug_println!("[PERF] backfill END: {:.2}ms", total_duration.as_secs_f64() * 1000.0);
     
-    Ok(format!(
-        "Scanned log file: found {} joins and {} leaves",
-        join_count, leave_count
-    ))
+    Ok(BackfillSummary {
+        file_name,
+        joins: join_count,
+        leaves: leave_count,
+        anchor_found: found_joining,
+        empty: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// classify_line against the built-in markers, independent of the user's settings
+    fn classify(line: &str) -> ParsedEvent {
+        classify_line_with(line, &SessionMarkers::defaults())
+    }
+
+    const USER_ID: &str = "usr_40f4043b-87e3-42c5-ba1f-ed0ad22e49e5";
+    const WORLD_ID: &str = "wrld_4432ea9b-729c-46e3-8eaf-846aa0a37fdd";
+
+    fn tags(instance_type: Option<&str>, group_id: Option<&str>, region: Option<&str>) -> InstanceTags {
+        InstanceTags {
+            instance_type: instance_type.map(str::to_string),
+            group_id: group_id.map(str::to_string),
+            region: region.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn classifies_player_join_and_leave() {
+        let joined = format!("2024.01.05 21:14:03 Log        -  [Behaviour] OnPlayerJoined Lamp? ({})", USER_ID);
+        assert_eq!(
+            classify(&joined),
+            ParsedEvent::Player { joined: true, username: "Lamp?".to_string(), user_id: USER_ID.to_string() }
+        );
+        let left = format!("2024.01.05 21:20:41 Log        -  [Behaviour] OnPlayerLeft Some Name With Spaces ({})", USER_ID);
+        assert_eq!(
+            classify(&left),
+            ParsedEvent::Player { joined: false, username: "Some Name With Spaces".to_string(), user_id: USER_ID.to_string() }
+        );
+    }
+
+    #[test]
+    fn classifies_joining_world_with_tags() {
+        let line = format!(
+            "2024.01.05 21:13:58 Log        -  [Behaviour] Joining {}:12345~group(grp_0a1b2c3d-4e5f-6789-abcd-ef0123456789)~groupAccessType(members)~region(eu)",
+            WORLD_ID
+        );
+        assert_eq!(
+            classify(&line),
+            ParsedEvent::JoiningWorld {
+                world_id: Some(WORLD_ID.to_string()),
+                instance_id: Some("12345".to_string()),
+                tags: tags(Some("group"), Some("grp_0a1b2c3d-4e5f-6789-abcd-ef0123456789"), Some("eu")),
+            }
+        );
+    }
+
+    #[test]
+    fn classifies_joining_world_without_tags() {
+        let line = format!("2021.06.01 10:00:00 Log        -  [Behaviour] Joining {}:67890", WORLD_ID);
+        assert_eq!(
+            classify(&line),
+            ParsedEvent::JoiningWorld {
+                world_id: Some(WORLD_ID.to_string()),
+                instance_id: Some("67890".to_string()),
+                tags: InstanceTags::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn classifies_joining_room() {
+        let line = "2024.01.05 21:13:58 Log        -  [Behaviour] Joining or Creating Room: The Black Cat ";
+        assert_eq!(classify(line), ParsedEvent::JoiningRoom { room_name: "The Black Cat".to_string() });
+    }
+
+    #[test]
+    fn classifies_session_boundaries() {
+        assert_eq!(
+            classify("2024.01.05 21:14:02 Log        -  [Behaviour] Successfully joined room"),
+            ParsedEvent::JoinedRoom
+        );
+        assert_eq!(
+            classify("2024.01.05 22:01:10 Log        -  [Behaviour] OnLeftRoom"),
+            ParsedEvent::LeftRoom
+        );
+    }
+
+    #[test]
+    fn classifies_moderation() {
+        let banned = r#"2024.01.05 21:30:00 Log        -  Admin "Mod Person" banned player "Crasher" for the following reason: "Crashing the instance""#;
+        assert_eq!(
+            classify(banned),
+            ParsedEvent::Moderation {
+                admin: "Mod Person".to_string(),
+                action: "ban".to_string(),
+                target: "Crasher".to_string(),
+                reason: "Crashing the instance".to_string(),
+            }
+        );
+        let warned = r#"2024.01.05 21:31:00 Log        -  Admin "Mod Person" warned player "Loud" for the following reason: "Mic spam""#;
+        assert!(matches!(classify(warned), ParsedEvent::Moderation { ref action, .. } if action == "warn"));
+    }
+
+    #[test]
+    fn classifies_authenticated_user() {
+        let line = format!("2024.01.05 21:12:00 Log        -  [Behaviour] User Authenticated: - Lumine - ({})", USER_ID);
+        assert_eq!(
+            classify(&line),
+            ParsedEvent::Authenticated { username: "- Lumine -".to_string(), user_id: USER_ID.to_string() }
+        );
+    }
+
+    #[test]
+    fn classifies_unrelated_lines_as_other() {
+        for line in [
+            "2024.01.05 21:12:00 Log        -  [Always] Initializing VRCAudio...",
+            "2024.01.05 21:12:01 Warning    -  [Behaviour] OnPlayerJoined without an id",
+            "",
+        ] {
+            assert_eq!(classify(line), ParsedEvent::Other, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn parses_region_only_as_public() {
+        assert_eq!(parse_instance_tags("~region(us)"), tags(Some("public"), None, Some("us")));
+    }
+
+    #[test]
+    fn parses_group_access_types() {
+        assert_eq!(
+            parse_instance_tags("~group(grp_x)~groupAccessType(public)~region(jp)"),
+            tags(Some("group_public"), Some("grp_x"), Some("jp"))
+        );
+        assert_eq!(
+            parse_instance_tags("~group(grp_x)~groupAccessType(plus)"),
+            tags(Some("group+"), Some("grp_x"), None)
+        );
+    }
+
+    #[test]
+    fn parses_friends_and_hidden() {
+        let nonce = "~nonce(0f3a4b5c-6d7e-8f90-a1b2-c3d4e5f60718)";
+        assert_eq!(
+            parse_instance_tags(&format!("~friends({}){}~region(eu)", USER_ID, nonce)),
+            tags(Some("friends"), None, Some("eu"))
+        );
+        assert_eq!(
+            parse_instance_tags(&format!("~hidden({}){}", USER_ID, nonce)),
+            tags(Some("friends+"), None, None)
+        );
+    }
+
+    #[test]
+    fn parses_private_as_invite() {
+        assert_eq!(
+            parse_instance_tags(&format!("~private({})~canRequestInvite~region(use)~nonce(abc)", USER_ID)),
+            tags(Some("invite+"), None, Some("use"))
+        );
+        assert_eq!(
+            parse_instance_tags(&format!("~private({})~nonce(abc)", USER_ID)),
+            tags(Some("invite"), None, None)
+        );
+    }
+
+    #[test]
+    fn no_tags_leaves_type_unknown() {
+        assert_eq!(parse_instance_tags(""), InstanceTags::default());
+    }
 }
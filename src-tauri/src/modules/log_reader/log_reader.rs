@@ -6,13 +6,17 @@ use std::{
     fs::{self, File},
     io::{Read, Seek, SeekFrom},
     path::PathBuf,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use crate::modules::log_reader::log_parser;
-use tauri::AppHandle;
+use crate::modules::settings::settings;
+use tauri::{AppHandle, Emitter};
 
 // Context for tracking state of each log file
 struct LogContext {
@@ -25,10 +29,32 @@ pub struct LogReader {
     log_directory: PathBuf,
     log_contexts: Arc<Mutex<HashMap<String, LogContext>>>,
     thread_handle: Option<thread::JoinHandle<()>>,
-    active: Arc<Mutex<bool>>,
+    active: Arc<AtomicBool>, // Cleared by stop() to end the polling loop
     app_handle: Option<AppHandle>,
 }
 
+/// How often the polling loop checks the active flag while waiting for the next poll,
+/// so stop() returns promptly instead of waiting out a full poll interval
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Poll interval bounds and default (see set_log_poll_interval for the tradeoff)
+const POLL_INTERVAL_DEFAULT_MS: u64 = 1000;
+const POLL_INTERVAL_MIN_MS: u64 = 100;
+const POLL_INTERVAL_MAX_MS: u64 = 5000;
+
+/// The log directory is re-listed (to discover new/rotated log files) at most this often,
+/// no matter how tight the poll interval is. Between listings only known files are checked.
+const DISCOVERY_INTERVAL: Duration = Duration::from_millis(1000);
+
+// Current poll interval, read by the polling loop each iteration so changes apply immediately
+static POLL_INTERVAL_MS: AtomicU64 = AtomicU64::new(POLL_INTERVAL_DEFAULT_MS);
+
+fn clamp_poll_interval(interval_ms: Option<u64>) -> u64 {
+    interval_ms
+        .unwrap_or(POLL_INTERVAL_DEFAULT_MS)
+        .clamp(POLL_INTERVAL_MIN_MS, POLL_INTERVAL_MAX_MS)
+}
+
 impl LogReader {
     // Step 1: Initialization
     pub fn new(app_handle: AppHandle) -> Self {
@@ -38,7 +64,7 @@ impl LogReader {
             log_directory: log_dir,
             log_contexts: Arc::new(Mutex::new(HashMap::new())),
             thread_handle: None,
-            active: Arc::new(Mutex::new(false)),
+            active: Arc::new(AtomicBool::new(false)),
             app_handle: Some(app_handle),
         }
     }
@@ -50,49 +76,173 @@ impl LogReader {
         let active = Arc::clone(&self.active);
         let app_handle = self.app_handle.clone().expect("AppHandle not set");
         
-        *active.lock().unwrap() = true;
+        active.store(true, Ordering::SeqCst);
+        load_config();
         
         let handle = thread::spawn(move || {
+            // Seed the self user id from existing logs so is_self works before a new login line
+            log_parser::refresh_authenticated_user();
+            
             // Step 2 & 3: File Discovery and Incremental Reading loop
-            while *active.lock().unwrap() {
-                if let Err(e) = update_logs(&directory, &contexts, &app_handle) {
-                    crate::debug_eprintln!("[log_reader] Error updating logs: {}", e);
+            let mut stop_error: Option<String> = None;
+            let mut known_files: Vec<(String, PathBuf)> = Vec::new();
+            let mut last_discovery: Option<Instant> = None;
+            while active.load(Ordering::SeqCst) {
+                let rediscover = last_discovery.map(|t| t.elapsed() >= DISCOVERY_INTERVAL).unwrap_or(true);
+                if rediscover {
+                    last_discovery = Some(Instant::now());
+                }
+                // A panic while parsing must not silently kill monitoring - report it and stop
+                let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    update_logs(&directory, &contexts, &app_handle, &mut known_files, rediscover)
+                }));
+                match result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => {
+                        crate::debug_eprintln!("[log_reader] Error updating logs: {}", e);
+                        emit_watcher_error(&app_handle, &e.to_string(), None);
+                    }
+                    Err(panic) => {
+                        let message = panic
+                            .downcast_ref::<&str>()
+                            .map(|s| s.to_string())
+                            .or_else(|| panic.downcast_ref::<String>().cloned())
+                            .unwrap_or_else(|| "log reader panicked".to_string());
+                        stop_error = Some(message);
+                        break;
+                    }
                 }
                 
-                // Poll every second (mimics VRCX's 1 second polling)
-                thread::sleep(Duration::from_secs(1));
+                // Poll at the configured interval (default 1s, mimics VRCX), waking early if stopped
+                let next_poll = Instant::now() + Duration::from_millis(POLL_INTERVAL_MS.load(Ordering::SeqCst));
+                while active.load(Ordering::SeqCst) && Instant::now() < next_poll {
+                    thread::sleep(STOP_CHECK_INTERVAL);
+                }
             }
+            active.store(false, Ordering::SeqCst);
+            // Let the UI know monitoring ended (error is null for a normal stop)
+            let _ = app_handle.emit("watcher_stopped", serde_json::json!({
+                "error": stop_error,
+                "timestamp": chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+            }));
         });
         
         self.thread_handle = Some(handle);
     }
 
+    // False once stop() was called or the polling thread ended on its own
+    fn is_running(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+            && self.thread_handle.as_ref().map(|h| !h.is_finished()).unwrap_or(false)
+    }
+
     pub fn stop(&mut self) {
-        *self.active.lock().unwrap() = false;
+        // The loop exits after finishing its current iteration
+        self.active.store(false, Ordering::SeqCst);
         if let Some(handle) = self.thread_handle.take() {
             let _ = handle.join();
         }
     }
 }
 
+/// What the single-reader guard needs from a reader (a fake stands in for LogReader in tests)
+trait Watcher {
+    fn is_running(&self) -> bool;
+    fn stop(&mut self);
+}
+
+impl Watcher for LogReader {
+    fn is_running(&self) -> bool {
+        LogReader::is_running(self)
+    }
+
+    fn stop(&mut self) {
+        LogReader::stop(self)
+    }
+}
+
+/// Put a freshly spawned reader in `slot` unless one is already running there.
+/// A reader whose loop died is stopped and replaced. Returns true if `spawn` was called.
+fn start_once<W: Watcher>(slot: &mut Option<W>, spawn: impl FnOnce() -> W) -> bool {
+    if let Some(existing) = slot.as_mut() {
+        if existing.is_running() {
+            return false;
+        }
+        // The previous loop died (watcher_stopped was emitted) - clean it up and start fresh
+        existing.stop();
+    }
+    *slot = Some(spawn());
+    true
+}
+
+/// Whether a log reader thread is currently running
+pub fn is_log_reader_running() -> bool {
+    LOG_READER
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|r| r.is_running()))
+        .unwrap_or(false)
+}
+
+// Snapshot of what the polling loop is doing, for diagnostics (get_watcher_status)
+#[derive(Default, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WatcherStatus {
+    current_path: Option<String>, // Log file that most recently received new lines
+    last_offset: u64,             // Read position in current_path (bytes)
+    file_size: u64,               // Size of current_path at the last poll
+    last_event_ts: Option<String>, // When the last line was processed (local time)
+    last_poll_ts: Option<String>, // When the loop last ran
+}
+
 // Global log reader instance
 lazy_static::lazy_static! {
     static ref LOG_READER: Mutex<Option<LogReader>> = Mutex::new(None);
     static ref MOST_RECENT_LOG_FILE: Mutex<Option<String>> = Mutex::new(None);
+    static ref NEWEST_LOG_FILE: Mutex<Option<String>> = Mutex::new(None);
+    static ref WATCHER_STATUS: Mutex<WatcherStatus> = Mutex::new(WatcherStatus::default());
+}
+
+// Tauri command to get the log reader's current state.
+// A stalled reader shows up as lastOffset not advancing while fileSize grows.
+#[tauri::command]
+pub fn get_watcher_status() -> Result<serde_json::Value, String> {
+    let running = is_log_reader_running();
+    let status = WATCHER_STATUS.lock().map_err(|e| e.to_string())?.clone();
+    let mut value = serde_json::to_value(status).map_err(|e| e.to_string())?;
+    value["running"] = serde_json::Value::Bool(running);
+    Ok(value)
+}
+
+// Tauri command to recover the live view after a UI reload without restarting the reader.
+// Returns { watcher, instance, activeUsers } (same shapes as get_watcher_status,
+// get_current_instance and the http API's /active-users). With emit, the same payload
+// also goes out as live_view_resync so every open window can rebuild its state.
+#[tauri::command]
+pub fn resync_live_view(app_handle: tauri::AppHandle, emit: Option<bool>) -> Result<serde_json::Value, String> {
+    let snapshot = serde_json::json!({
+        "watcher": get_watcher_status()?,
+        "instance": log_parser::get_current_instance()?,
+        "activeUsers": log_parser::get_active_players()
+    });
+    if emit.unwrap_or(false) {
+        let _ = app_handle.emit("live_view_resync", &snapshot);
+    }
+    Ok(snapshot)
 }
 
 // Tauri command to start the log reader
+// Pages call this on every mount, so a second call while a reader is running is a no-op.
+// The check and insert happen under the LOG_READER lock, so two calls can never both spawn a loop.
 #[tauri::command]
 pub fn start_log_reader(app_handle: tauri::AppHandle) -> Result<(), String> {
     let mut reader_guard = LOG_READER.lock().map_err(|e| e.to_string())?;
     
-    if reader_guard.is_some() {
-        return Err("Log reader already started".to_string());
-    }
-    
-    let mut reader = LogReader::new(app_handle);
-    reader.start();
-    *reader_guard = Some(reader);
+    start_once(&mut reader_guard, || {
+        let mut reader = LogReader::new(app_handle);
+        reader.start();
+        reader
+    });
     
     Ok(())
 }
@@ -258,19 +408,51 @@ pub fn open_most_recent_log_folder() -> Result<String, String> {
     }
 }
 
-// Step 2: File Discovery - Find and track all output_log_*.txt files
-fn update_logs(
-    log_dir: &PathBuf,
-    contexts: &Arc<Mutex<HashMap<String, LogContext>>>,
-    app_handle: &AppHandle,
-) -> Result<(), Box<dyn std::error::Error>> {
+/// Emit a recoverable per-iteration failure so the UI can show that monitoring is degraded
+fn emit_watcher_error(app_handle: &AppHandle, error: &str, file: Option<&str>) {
+    match file {
+        Some(name) => {
+            crate::debug_error!(app_handle, "[log_reader] Error reading {}: {}", name, error);
+        }
+        None => {
+            crate::debug_error!(app_handle, "[log_reader] {}", error);
+        }
+    }
+    let _ = app_handle.emit("watcher_error", serde_json::json!({
+        "error": error,
+        "file": file,
+        "timestamp": chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+    }));
+}
+
+/// Load the poll interval from settings (reader start and after a backup restore)
+pub fn load_config() {
+    if let Ok(s) = settings::get_settings() {
+        POLL_INTERVAL_MS.store(clamp_poll_interval(s.poll_interval_ms), Ordering::SeqCst);
+    }
+}
+
+/// Set how often the log reader polls for new lines (milliseconds, clamped to 100-5000).
+/// Lower values deliver join alerts sooner but wake the CPU more often; each poll stats the
+/// tracked log files and reads any new bytes. Higher values save CPU/battery at the cost of latency.
+/// Pass None to restore the default (1000ms).
+#[tauri::command]
+pub fn set_log_poll_interval(interval_ms: Option<u64>) -> Result<u64, String> {
+    let effective = clamp_poll_interval(interval_ms);
+    settings::update_settings(|s| s.poll_interval_ms = interval_ms.map(|_| effective))?;
+    POLL_INTERVAL_MS.store(effective, Ordering::SeqCst);
+    Ok(effective)
+}
+
+// List all output_log_*.txt files in the log directory, oldest modification first
+fn discover_log_files(log_dir: &PathBuf) -> Vec<(String, PathBuf)> {
     // Refresh directory to get latest files
     let entries = match fs::read_dir(log_dir) {
         Ok(e) => e,
-        Err(_) => return Ok(()), // Directory doesn't exist yet, skip
+        Err(_) => return Vec::new(), // Directory doesn't exist yet, skip
     };
 
-    let mut file_infos: Vec<(String, PathBuf, u64)> = Vec::new();
+    let mut file_infos: Vec<(String, PathBuf)> = Vec::new();
     
     // Collect all matching log files
     for entry in entries.flatten() {
@@ -281,27 +463,93 @@ fn update_logs(
         };
         
         // Match pattern: output_log_*.txt
+        // Note: We track ALL active log files, but skip historical content when reading
+        // (VRCX filters log LINES by date, not files themselves)
         if !name.starts_with("output_log_") || !name.ends_with(".txt") {
             continue;
         }
         
-        // Get file metadata
-        // Note: We track ALL active log files, but skip historical content when reading
-        // (VRCX filters log LINES by date, not files themselves)
-        if let Ok(metadata) = entry.metadata() {
-            let length = metadata.len();
-            let path_clone = path.clone(); // Clone path before moving
-            file_infos.push((name.to_string(), path_clone, length));
-        }
+        file_infos.push((name.to_string(), path));
     }
     
     // Sort by modification time (oldest first) - similar to VRCX
-    file_infos.sort_by_key(|(_, path, _)| {
+    file_infos.sort_by_key(|(_, path)| {
         fs::metadata(path)
             .and_then(|m| m.modified())
             .unwrap_or(std::time::SystemTime::UNIX_EPOCH)
     });
-    
+    file_infos
+}
+
+// Record the file that just received new lines as the most recent one.
+// When a newer session log takes over (VRChat restarted), emit log_file_rotated with the
+// old and new paths so views reading the latest file by offset can reset to 0.
+fn note_most_recent_file(log_dir: &PathBuf, name: &str, app_handle: &AppHandle) {
+    if let Ok(mut recent) = MOST_RECENT_LOG_FILE.lock() {
+        *recent = Some(name.to_string());
+    }
+    let replaced = match NEWEST_LOG_FILE.lock() {
+        Ok(mut newest) => advance_newest(&mut newest, name),
+        Err(_) => return,
+    };
+    if let Some(old) = replaced {
+        let _ = app_handle.emit("log_file_rotated", serde_json::json!({
+            "oldPath": log_dir.join(&old).to_string_lossy(),
+            "newPath": log_dir.join(name).to_string_lossy(),
+            "oldFile": old,
+            "newFile": name,
+            "timestamp": chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+        }));
+    }
+}
+
+/// Move the newest-session marker forward to `name` if it sorts after the current one.
+/// Log names embed their start time (output_log_YYYY-MM-DD_HH-MM-SS.txt), so an older file
+/// that is still being written (second client) never moves it back. Returns the name it
+/// replaced, or None when nothing changed or this is the first file seen.
+fn advance_newest(newest: &mut Option<String>, name: &str) -> Option<String> {
+    match newest {
+        Some(current) if name > current.as_str() => Some(std::mem::replace(current, name.to_string())),
+        Some(_) => None,
+        None => {
+            *newest = Some(name.to_string());
+            None
+        }
+    }
+}
+
+// Step 2: File Discovery - Find and track all output_log_*.txt files
+// The directory is only re-listed when rediscover is true; otherwise the previously
+// discovered files are re-checked for growth.
+fn update_logs(
+    log_dir: &PathBuf,
+    contexts: &Arc<Mutex<HashMap<String, LogContext>>>,
+    app_handle: &AppHandle,
+    known_files: &mut Vec<(String, PathBuf)>,
+    rediscover: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if rediscover {
+        *known_files = discover_log_files(log_dir);
+    }
+
+    // Get current lengths (files are kept in modification order from discovery)
+    let file_infos: Vec<(String, PathBuf, u64)> = known_files
+        .iter()
+        .filter_map(|(name, path)| {
+            fs::metadata(path).ok().map(|m| (name.clone(), path.clone(), m.len()))
+        })
+        .collect();
+
+    // Refresh diagnostics: poll time and the current file's size (even if we don't read it this poll)
+    if let Ok(mut status) = WATCHER_STATUS.lock() {
+        status.last_poll_ts = Some(chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+        let recent = MOST_RECENT_LOG_FILE.lock().ok().and_then(|r| r.clone());
+        if let Some((_, path, len)) = file_infos.iter().find(|(name, _, _)| Some(name) == recent.as_ref()) {
+            status.current_path = Some(path.to_string_lossy().to_string());
+            status.file_size = *len;
+        }
+    }
+
     // Collect files that need reading (while holding lock briefly)
     let mut files_to_read: Vec<(String, PathBuf)> = Vec::new();
     
@@ -317,7 +565,7 @@ fn update_logs(
                     // File has new content, update length
                     context.length = current_length;
                     // Track most recently updated file
-                    *MOST_RECENT_LOG_FILE.lock().unwrap() = Some(name.clone());
+                    note_most_recent_file(log_dir, &name, app_handle);
                     true
                 } else {
                     false
@@ -347,6 +595,7 @@ fn update_logs(
         // Step 3: Incremental Reading - Read new lines from this file
         if let Err(e) = parse_log_file(&path, &name, contexts, app_handle) {
             crate::debug_eprintln!("[log_reader] Error parsing log file {}: {}", name, e);
+            emit_watcher_error(app_handle, &e.to_string(), Some(&name));
         }
     }
     
@@ -498,6 +747,15 @@ fn parse_log_file(
         context.position = current_file_position;
     }
     
+    if let Ok(mut status) = WATCHER_STATUS.lock() {
+        status.current_path = Some(file_path.to_string_lossy().to_string());
+        status.last_offset = current_file_position;
+        status.file_size = actual_file_size;
+        if line_count > 0 {
+            status.last_event_ts = Some(chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+        }
+    }
+    
     Ok(())
 }
 
@@ -509,3 +767,83 @@ fn default_vrchat_log_dir() -> PathBuf {
         .unwrap_or_else(|| PathBuf::from("C:/Users/Public"));
     local_low.join("LocalLow").join("VRChat").join("VRChat")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    struct FakeWatcher {
+        running: bool,
+        stopped: Arc<AtomicBool>,
+    }
+
+    impl Watcher for FakeWatcher {
+        fn is_running(&self) -> bool {
+            self.running
+        }
+
+        fn stop(&mut self) {
+            self.running = false;
+            self.stopped.store(true, Ordering::SeqCst);
+        }
+    }
+
+    fn running_fake(spawned: &AtomicUsize) -> FakeWatcher {
+        spawned.fetch_add(1, Ordering::SeqCst);
+        FakeWatcher { running: true, stopped: Arc::new(AtomicBool::new(false)) }
+    }
+
+    #[test]
+    fn second_start_does_not_spawn_another_loop() {
+        let spawned = AtomicUsize::new(0);
+        let mut slot = None;
+        assert!(start_once(&mut slot, || running_fake(&spawned)));
+        assert!(!start_once(&mut slot, || running_fake(&spawned)));
+        assert_eq!(spawned.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn dead_reader_is_stopped_and_replaced() {
+        let spawned = AtomicUsize::new(0);
+        let old_stopped = Arc::new(AtomicBool::new(false));
+        let mut slot = Some(FakeWatcher { running: false, stopped: Arc::clone(&old_stopped) });
+        assert!(start_once(&mut slot, || running_fake(&spawned)));
+        assert!(old_stopped.load(Ordering::SeqCst));
+        assert_eq!(spawned.load(Ordering::SeqCst), 1);
+        assert!(slot.as_ref().is_some_and(|w| w.is_running()));
+    }
+
+    #[test]
+    fn concurrent_starts_spawn_one_loop() {
+        let spawned = Arc::new(AtomicUsize::new(0));
+        let slot: Arc<Mutex<Option<FakeWatcher>>> = Arc::new(Mutex::new(None));
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let spawned = Arc::clone(&spawned);
+                let slot = Arc::clone(&slot);
+                thread::spawn(move || {
+                    let mut guard = slot.lock().unwrap();
+                    start_once(&mut guard, || running_fake(&spawned));
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(spawned.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn interleaved_logs_rotate_only_once() {
+        let older = "output_log_2024-01-05_09-00-00.txt";
+        let newer = "output_log_2024-01-05_21-00-00.txt";
+        let mut newest = None;
+        let emits: Vec<String> = [older, newer, older, newer]
+            .iter()
+            .filter_map(|name| advance_newest(&mut newest, name))
+            .collect();
+        assert_eq!(emits, vec![older.to_string()]);
+        assert_eq!(newest.as_deref(), Some(newer));
+    }
+}
@@ -3,6 +3,12 @@
 pub mod log_reader;
 pub mod log_parser;
 pub mod event_exporter;
+pub mod patterns;
+pub mod line_index;
+pub mod archive;
+pub mod session_markers;
+pub mod rejoin_storm;
+pub mod join_grace;
 
 // Re-export for convenience (if needed elsewhere)
 // pub use log_reader::LogReader;
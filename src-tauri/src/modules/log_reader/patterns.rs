@@ -0,0 +1,123 @@
+// Patterns: Shared regexes for VRChat identifiers found in log lines
+//
+// Every user id match in the backend should go through these so the accepted
+// id shape is defined in exactly one place.
+//
+// VRChat user ids are normally `usr_` followed by a 36 character lowercase UUID,
+// but ids with other lengths and uppercase hex have shown up in real logs.
+// We accept 8-64 hex/dash characters (either case) after the prefix so those
+// players are not silently dropped, while still rejecting obvious garbage.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Unanchored user id pattern, for embedding in larger regexes.
+/// Example: usr_40f4043b-87e3-42c5-ba1f-ed0ad22e49e5
+pub const USER_ID_PATTERN: &str = r"usr_[0-9a-fA-F-]{8,64}";
+
+lazy_static! {
+    // Anchored form of USER_ID_PATTERN, for validating ids entered by hand
+    pub static ref USER_ID_REGEX: Regex = Regex::new(
+        &format!(r"^{}$", USER_ID_PATTERN)
+    ).expect("Failed to compile user id regex");
+
+    // Exact shape of a stored log timestamp (YYYY.MM.DD HH:MM:SS, zero padded).
+    // Stored rows are ordered by string comparison, so anything else sorts wrong.
+    pub static ref LOG_TIMESTAMP_REGEX: Regex = Regex::new(
+        r"^\d{4}\.\d{2}\.\d{2} \d{2}:\d{2}:\d{2}$"
+    ).expect("Failed to compile log timestamp regex");
+
+    // Regex pattern to match OnPlayerJoined/OnPlayerLeft events
+    // Format: OnPlayerJoined <username> (usr_<uuid>)
+    // Example: OnPlayerJoined Lamp? (usr_40f4043b-87e3-42c5-ba1f-ed0ad22e49e5)
+    // Captures: 1 = Joined|Left, 2 = username, 3 = full user id (including usr_)
+    pub static ref PLAYER_JOIN_LEAVE_REGEX: Regex = Regex::new(
+        &format!(r"OnPlayer(Joined|Left)\s+(.+?)\s+\(({})\)", USER_ID_PATTERN)
+    ).expect("Failed to compile player join/leave regex");
+
+    // Regex pattern to match the local user's login line
+    // Format: [Behaviour] User Authenticated: <username> (usr_<uuid>)
+    // Example: [Behaviour] User Authenticated: - Lumine - (usr_40f4043b-87e3-42c5-ba1f-ed0ad22e49e5)
+    // Captures: 1 = username, 2 = full user id (including usr_)
+    pub static ref USER_AUTHENTICATED_REGEX: Regex = Regex::new(
+        &format!(r"User\s+Authenticated:\s*(.+?)\s+\(({})\)", USER_ID_PATTERN)
+    ).expect("Failed to compile user authenticated regex");
+}
+
+/// True when the whole string is a user id we would accept from a log line
+pub fn is_valid_user_id(user_id: &str) -> bool {
+    USER_ID_REGEX.is_match(user_id)
+}
+
+/// True if `ts` is a zero-padded log timestamp that sorts correctly as a string
+pub fn is_valid_log_ts(ts: &str) -> bool {
+    LOG_TIMESTAMP_REGEX.is_match(ts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_real_world_user_ids() {
+        for id in [
+            "usr_40f4043b-87e3-42c5-ba1f-ed0ad22e49e5", // standard lowercase UUID
+            "usr_40F4043B-87E3-42C5-BA1F-ED0AD22E49E5", // uppercase hex
+            "usr_0a1b2c3d",                             // short legacy id
+            "usr_0a1b2c3d4e5f",
+        ] {
+            assert!(is_valid_user_id(id), "{}", id);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_user_ids() {
+        let too_long = format!("usr_{}", "a".repeat(65));
+        for id in [
+            "usr_",
+            "usr_0a1b2c3",                              // 7 characters, below the minimum
+            "usr_zzzzzzzz-87e3-42c5-ba1f-ed0ad22e49e5", // non-hex
+            "usr_40f4043b_87e3",                        // underscore separator
+            "40f4043b-87e3-42c5-ba1f-ed0ad22e49e5",     // missing prefix
+            " usr_40f4043b-87e3-42c5-ba1f-ed0ad22e49e5",
+            too_long.as_str(),
+        ] {
+            assert!(!is_valid_user_id(id), "{}", id);
+        }
+        assert!(is_valid_user_id(&format!("usr_{}", "a".repeat(64))));
+    }
+
+    #[test]
+    fn join_leave_regex_captures_user_id() {
+        let line = "2024.01.05 21:14:03 Log        -  [Behaviour] OnPlayerJoined Lamp? (usr_40F4043B-87E3-42C5-BA1F-ED0AD22E49E5)";
+        let caps = PLAYER_JOIN_LEAVE_REGEX.captures(line).expect("join line should match");
+        assert_eq!(&caps[1], "Joined");
+        assert_eq!(&caps[2], "Lamp?");
+        assert_eq!(&caps[3], "usr_40F4043B-87E3-42C5-BA1F-ED0AD22E49E5");
+
+        let legacy = "[Behaviour] OnPlayerLeft Old Account (usr_0a1b2c3d)";
+        let caps = PLAYER_JOIN_LEAVE_REGEX.captures(legacy).expect("legacy id should match");
+        assert_eq!(&caps[1], "Left");
+        assert_eq!(&caps[3], "usr_0a1b2c3d");
+    }
+
+    #[test]
+    fn join_leave_regex_ignores_bad_ids() {
+        let too_long = format!("[Behaviour] OnPlayerJoined Name (usr_{})", "a".repeat(65));
+        for line in [
+            "[Behaviour] OnPlayerJoined Name (usr_)",
+            "[Behaviour] OnPlayerJoined Name (usr_not-a-real-id)",
+            too_long.as_str(),
+        ] {
+            assert!(PLAYER_JOIN_LEAVE_REGEX.captures(line).is_none(), "{}", line);
+        }
+    }
+
+    #[test]
+    fn authenticated_regex_captures_user_id() {
+        let line = "[Behaviour] User Authenticated: - Lumine - (usr_40f4043b-87e3-42c5-ba1f-ed0ad22e49e5)";
+        let caps = USER_AUTHENTICATED_REGEX.captures(line).expect("authenticated line should match");
+        assert_eq!(&caps[1], "- Lumine -");
+        assert_eq!(&caps[2], "usr_40f4043b-87e3-42c5-ba1f-ed0ad22e49e5");
+    }
+}
@@ -0,0 +1,104 @@
+// Rejoin Storm: Detect users cycling in and out of the instance
+//
+// A crasher probing the instance or a ban-evader tends to join, leave and rejoin
+// in quick succession. Live joins are counted per user over a rolling window; once
+// a user reaches the threshold the log parser emits rejoin_storm (and optionally
+// shows a toast). Counts reset whenever we change instance.
+
+use lazy_static::lazy_static;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_THRESHOLD: u64 = 3;
+const DEFAULT_WINDOW_SECS: u64 = 60;
+
+/// Joins within the window that count as a storm (from settings, see set_rejoin_storm_config)
+static THRESHOLD: AtomicU64 = AtomicU64::new(DEFAULT_THRESHOLD);
+/// Rolling window length in seconds
+static WINDOW_SECS: AtomicU64 = AtomicU64::new(DEFAULT_WINDOW_SECS);
+/// Show a toast when a storm starts (the event is always emitted)
+static ALERT: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    /// Recent live join times per user id (oldest first)
+    static ref RECENT_JOINS: Mutex<HashMap<String, VecDeque<Instant>>> = Mutex::new(HashMap::new());
+}
+
+fn clamp_threshold(threshold: Option<u64>) -> u64 {
+    threshold.unwrap_or(DEFAULT_THRESHOLD).clamp(2, 50)
+}
+
+fn clamp_window_secs(secs: Option<u64>) -> u64 {
+    secs.unwrap_or(DEFAULT_WINDOW_SECS).clamp(5, 3600)
+}
+
+/// Load the threshold/window/alert from settings (startup and after a backup restore)
+pub fn load_config() {
+    if let Ok(s) = crate::modules::settings::settings::get_settings() {
+        THRESHOLD.store(clamp_threshold(s.rejoin_storm_threshold), Ordering::SeqCst);
+        WINDOW_SECS.store(clamp_window_secs(s.rejoin_storm_window_secs), Ordering::SeqCst);
+        ALERT.store(s.rejoin_storm_alert, Ordering::SeqCst);
+    }
+}
+
+/// Current window length in seconds
+pub fn window_secs() -> u64 {
+    WINDOW_SECS.load(Ordering::SeqCst)
+}
+
+/// True when a storm that just reached `count` joins should show a toast.
+/// Only the join that crosses the threshold alerts; later joins in the same storm just emit.
+pub fn should_alert(count: usize) -> bool {
+    ALERT.load(Ordering::SeqCst) && count as u64 == THRESHOLD.load(Ordering::SeqCst)
+}
+
+/// Count a live join. Returns the number of joins inside the window when it has
+/// reached the threshold, None otherwise.
+pub fn record_join(user_id: &str) -> Option<usize> {
+    let now = Instant::now();
+    let window = Duration::from_secs(window_secs());
+    let mut recent = RECENT_JOINS.lock().ok()?;
+
+    // Drop users whose joins have all aged out so the map doesn't grow over a long session
+    recent.retain(|_, joins| joins.back().is_some_and(|last| now.duration_since(*last) < window));
+
+    let joins = recent.entry(user_id.to_string()).or_default();
+    joins.push_back(now);
+    while joins.front().is_some_and(|first| now.duration_since(*first) >= window) {
+        joins.pop_front();
+    }
+
+    let count = joins.len();
+    (count as u64 >= THRESHOLD.load(Ordering::SeqCst)).then_some(count)
+}
+
+/// Forget all join history (new instance session)
+pub fn clear() {
+    if let Ok(mut recent) = RECENT_JOINS.lock() {
+        recent.clear();
+    }
+}
+
+/// Set how many joins within window_secs count as a rejoin storm, and whether it shows a toast.
+/// Threshold is clamped to 2-50 and the window to 5-3600s; None restores the default (3 in 60s).
+/// Returns the effective values.
+#[tauri::command]
+pub fn set_rejoin_storm_config(threshold: Option<u64>, window_secs: Option<u64>, alert: bool) -> Result<serde_json::Value, String> {
+    let effective_threshold = clamp_threshold(threshold);
+    let effective_window = clamp_window_secs(window_secs);
+    crate::modules::settings::settings::update_settings(|s| {
+        s.rejoin_storm_threshold = threshold.map(|_| effective_threshold);
+        s.rejoin_storm_window_secs = window_secs.map(|_| effective_window);
+        s.rejoin_storm_alert = alert;
+    })?;
+    THRESHOLD.store(effective_threshold, Ordering::SeqCst);
+    WINDOW_SECS.store(effective_window, Ordering::SeqCst);
+    ALERT.store(alert, Ordering::SeqCst);
+    Ok(serde_json::json!({
+        "threshold": effective_threshold,
+        "windowSecs": effective_window,
+        "alert": alert
+    }))
+}
@@ -0,0 +1,128 @@
+// Session Markers: Configurable log lines that end an instance session
+//
+// "Left room" markers clear the location and roster; "joined room" markers start a fresh
+// roster for the new instance. VRChat occasionally rewords these lines between builds, so
+// the patterns come from settings (left_room_markers / joined_room_markers) with the
+// built-in defaults used when unset. Patterns are regexes, compiled once; invalid ones are
+// skipped and reported instead of panicking.
+
+use lazy_static::lazy_static;
+use regex::RegexSet;
+use std::sync::{Arc, RwLock};
+
+use crate::modules::settings::settings;
+
+/// Built-in "left the instance" markers
+pub const DEFAULT_LEFT_ROOM_MARKERS: &[&str] = &[r"\[Behaviour\] OnLeftRoom"];
+/// Built-in "joined a new instance" markers
+pub const DEFAULT_JOINED_ROOM_MARKERS: &[&str] = &[r"\[Behaviour\] Successfully joined room"];
+
+/// Compiled left/joined marker sets
+pub struct SessionMarkers {
+    left: RegexSet,
+    joined: RegexSet,
+}
+
+impl SessionMarkers {
+    /// The built-in markers, ignoring settings
+    pub fn defaults() -> Self {
+        SessionMarkers {
+            left: compile(&effective(None, DEFAULT_LEFT_ROOM_MARKERS)).0,
+            joined: compile(&effective(None, DEFAULT_JOINED_ROOM_MARKERS)).0,
+        }
+    }
+
+    /// True when the line means we left the instance
+    pub fn is_left_room(&self, line: &str) -> bool {
+        self.left.is_match(line)
+    }
+
+    /// True when the line means we joined a new instance
+    pub fn is_joined_room(&self, line: &str) -> bool {
+        self.joined.is_match(line)
+    }
+}
+
+lazy_static! {
+    static ref MARKERS: RwLock<Option<Arc<SessionMarkers>>> = RwLock::new(None);
+}
+
+/// Configured patterns, or the defaults when unset/empty
+fn effective(configured: Option<Vec<String>>, defaults: &[&str]) -> Vec<String> {
+    configured
+        .filter(|list| list.iter().any(|p| !p.trim().is_empty()))
+        .unwrap_or_else(|| defaults.iter().map(|s| s.to_string()).collect())
+}
+
+/// Compile the patterns that are valid; returns the set and the (pattern, error) pairs skipped
+fn compile(patterns: &[String]) -> (RegexSet, Vec<(String, String)>) {
+    let mut valid = Vec::new();
+    let mut invalid = Vec::new();
+    for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        match regex::Regex::new(pattern) {
+            Ok(_) => valid.push(pattern.to_string()),
+            Err(e) => {
+                crate::debug_eprintln!("[session_markers] Skipping invalid marker '{}': {}", pattern, e);
+                invalid.push((pattern.to_string(), e.to_string()));
+            }
+        }
+    }
+    let set = RegexSet::new(&valid).unwrap_or_else(|_| RegexSet::empty());
+    (set, invalid)
+}
+
+/// (Re)load markers from settings. Returns the invalid patterns that were skipped.
+pub fn reload() -> Vec<(String, String)> {
+    let s = settings::get_settings().unwrap_or_default();
+    let (left, mut invalid) = compile(&effective(s.left_room_markers, DEFAULT_LEFT_ROOM_MARKERS));
+    let (joined, invalid_joined) = compile(&effective(s.joined_room_markers, DEFAULT_JOINED_ROOM_MARKERS));
+    invalid.extend(invalid_joined);
+    if let Ok(mut markers) = MARKERS.write() {
+        *markers = Some(Arc::new(SessionMarkers { left, joined }));
+    }
+    invalid
+}
+
+/// The markers in effect (loaded from settings on first use)
+pub fn current() -> Arc<SessionMarkers> {
+    if MARKERS.read().map(|m| m.is_none()).unwrap_or(false) {
+        reload();
+    }
+    MARKERS
+        .read()
+        .ok()
+        .and_then(|m| m.clone())
+        .unwrap_or_else(|| Arc::new(SessionMarkers::defaults()))
+}
+
+/// Current marker patterns (configured or default) plus the defaults
+#[tauri::command]
+pub fn get_session_markers() -> Result<serde_json::Value, String> {
+    let s = settings::get_settings()?;
+    Ok(serde_json::json!({
+        "leftRoom": effective(s.left_room_markers, DEFAULT_LEFT_ROOM_MARKERS),
+        "joinedRoom": effective(s.joined_room_markers, DEFAULT_JOINED_ROOM_MARKERS),
+        "defaultLeftRoom": DEFAULT_LEFT_ROOM_MARKERS,
+        "defaultJoinedRoom": DEFAULT_JOINED_ROOM_MARKERS
+    }))
+}
+
+/// Set the marker regexes (None or an empty list restores the defaults) and apply them.
+/// Invalid patterns are saved but skipped; they are returned as [{ pattern, error }].
+#[tauri::command]
+pub fn set_session_markers(left_room: Option<Vec<String>>, joined_room: Option<Vec<String>>) -> Result<Vec<serde_json::Value>, String> {
+    let clean = |list: Option<Vec<String>>| {
+        list.map(|l| l.into_iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect::<Vec<_>>())
+            .filter(|l| !l.is_empty())
+    };
+    let left_room = clean(left_room);
+    let joined_room = clean(joined_room);
+    settings::update_settings(|s| {
+        s.left_room_markers = left_room;
+        s.joined_room_markers = joined_room;
+    })?;
+    Ok(reload()
+        .into_iter()
+        .map(|(pattern, error)| serde_json::json!({ "pattern": pattern, "error": error }))
+        .collect())
+}
@@ -8,4 +8,17 @@ pub mod instance_monitor;
 pub mod settings;
 pub mod sound;
 pub mod updater;
-pub mod debug;
\ No newline at end of file
+pub mod debug;
+pub mod app_state;
+pub mod notifications;
+pub mod tray;
+pub mod window_state;
+pub mod backup;
+pub mod database;
+pub mod watchlist;
+pub mod history;
+pub mod http_api;
+pub mod http_client;
+pub mod image_cache;
+pub mod health;
+pub mod vrchat_process;
\ No newline at end of file
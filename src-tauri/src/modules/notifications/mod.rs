@@ -0,0 +1,2 @@
+// Notifications module - native desktop notifications (toasts)
+pub mod toast;
@@ -0,0 +1,88 @@
+// Toast: Native Windows toast notifications
+//
+// Toasts are always silent - alert sounds are handled by the sound module so
+// volume/mute settings apply. On other platforms these functions are no-ops.
+
+use tauri::{AppHandle, Manager};
+
+/// Bring the main window to the front (restoring it if minimized or hidden)
+pub fn focus_main_window(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Show a silent toast with a title and one line of text. No-op outside Windows.
+/// With focus_user_id, clicking the toast focuses the app and emits focus_user { userId }
+/// so the UI can jump to that user's row.
+pub fn show_toast(
+    app_handle: &AppHandle,
+    title: &str,
+    body: &str,
+    focus_user_id: Option<String>,
+) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        use tauri::Emitter;
+        use tauri_winrt_notification::Toast;
+        
+        // Installed builds are registered under the bundle identifier; dev builds aren't,
+        // so borrow PowerShell's app id there or Windows drops the toast.
+        let app_id = if tauri::is_dev() {
+            Toast::POWERSHELL_APP_ID.to_string()
+        } else {
+            app_handle.config().identifier.clone()
+        };
+        
+        let app = app_handle.clone();
+        Toast::new(&app_id)
+            .title(title)
+            .text1(body)
+            .sound(None)
+            .on_activated(move |_action| {
+                focus_main_window(&app);
+                if let Some(ref user_id) = focus_user_id {
+                    let _ = app.emit("focus_user", serde_json::json!({ "userId": user_id }));
+                }
+                Ok(())
+            })
+            .show()
+            .map_err(|e| format!("Failed to show toast: {:?}", e))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app_handle, title, body, focus_user_id);
+        Ok(())
+    }
+}
+
+/// Show a toast for a group watchlist match (called when group_watch_results arrive)
+/// Clicking it focuses the app on user_id (if given)
+#[tauri::command]
+pub fn show_group_match_toast(
+    app_handle: AppHandle,
+    username: String,
+    group_name: Option<String>,
+    user_id: Option<String>,
+) -> Result<(), String> {
+    if let Some(id) = user_id.as_deref() {
+        let suppress = crate::modules::settings::settings::get_settings()
+            .map(|s| s.suppress_self_events.unwrap_or(true))
+            .unwrap_or(true);
+        if suppress && crate::modules::log_reader::log_parser::is_self_user(id) {
+            return Ok(());
+        }
+    }
+    let group = group_name
+        .map(|g| g.trim().to_string())
+        .filter(|g| !g.is_empty())
+        .unwrap_or_else(|| "a watched group".to_string());
+    show_toast(
+        &app_handle,
+        "Group watchlist match",
+        &format!("{} matched group {}", username, group),
+        user_id.filter(|id| !id.is_empty()),
+    )
+}
@@ -5,6 +5,7 @@
 
 use std::{fs, path::PathBuf};
 use serde::{Deserialize, Serialize};
+use crate::modules::database::connection;
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct AppSettings {
@@ -16,6 +17,72 @@ pub struct AppSettings {
     
     #[serde(default)]
     pub local_notifications: NotificationSettings,
+    
+    #[serde(default)]
+    pub log_timezone: Option<String>, // IANA zone name the VRChat logs were written in (None = system local)
+    
+    #[serde(default)]
+    pub debug_log_file: Option<String>, // Path debug messages are also written to (None = frontend only)
+    
+    #[serde(default)]
+    pub debug_log_max_bytes: Option<u64>, // Size at which the debug log file is rotated
+    
+    #[serde(default)]
+    pub poll_interval_ms: Option<u64>, // Log reader poll interval (None = default 1000ms)
+    
+    #[serde(default)]
+    pub backfill_scan_bytes: Option<u64>, // Initial log tail size searched by the backfill (None = 4MB)
+    
+    #[serde(default)]
+    pub notifications_muted: bool, // Global mute for all notification sounds (previews still play)
+    
+    #[serde(default)]
+    pub normalize_sounds: bool, // Scale notification sounds to a common peak level
+    
+    #[serde(default)]
+    pub suppress_self_events: Option<bool>, // Skip alerts for the local user (None = true)
+    
+    #[serde(default)]
+    pub group_check_debounce_ms: Option<u64>, // Quiet period before a group check batch is sent (None = 1000ms)
+    
+    #[serde(default)]
+    pub group_check_max_batch: Option<u64>, // Pending ids that force an immediate group check (None = 25)
+    
+    #[serde(default)]
+    pub http_api_enabled: bool, // Local read-only HTTP API for automation (off by default)
+    
+    #[serde(default)]
+    pub http_api_port: Option<u16>, // Port for the local HTTP API (None = 17420)
+    
+    #[serde(default)]
+    pub api_base_override: Option<String>, // FCH backend base URL for self-hosters (None = VITE_API_BASE / default)
+    
+    #[serde(default)]
+    pub left_room_markers: Option<Vec<String>>, // Regexes for "left the instance" lines (None = built-in)
+    
+    #[serde(default)]
+    pub joined_room_markers: Option<Vec<String>>, // Regexes for "joined a new instance" lines (None = built-in)
+    
+    #[serde(default)]
+    pub rejoin_storm_threshold: Option<u64>, // Joins within the window that count as a rejoin storm (None = 3)
+    
+    #[serde(default)]
+    pub rejoin_storm_window_secs: Option<u64>, // Rolling window for rejoin storm detection (None = 60s)
+    
+    #[serde(default)]
+    pub rejoin_storm_alert: bool, // Show a toast when a rejoin storm starts (event is always emitted)
+    
+    #[serde(default)]
+    pub join_grace_secs: Option<u64>, // Hide joins whose leave follows within this many seconds (None = off)
+    
+    #[serde(default)]
+    pub auto_start_watcher_when_vrchat_runs: bool, // Start/stop the log reader with the VRChat process
+    
+    #[serde(default)]
+    pub event_export_enabled: bool, // Append parsed events to events.jsonl (off by default)
+    
+    #[serde(default)]
+    pub event_export_max_bytes: Option<u64>, // Size at which events.jsonl is rotated (None = 10MB)
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -35,18 +102,12 @@ fn default_notification_volume() -> f64 {
     0.8
 }
 
-/// Get the directory where settings are stored
-fn settings_dir() -> PathBuf {
-    let base = std::env::var("LOCALAPPDATA")
-        .ok()
-        .map(PathBuf::from)
-        .unwrap_or_else(|| PathBuf::from("C:/Users/Public"));
-    base.join("FCHClient")
-}
+/// Backend used when neither the settings override nor VITE_API_BASE is set
+const DEFAULT_API_BASE: &str = "https://fch-toolkit.com";
 
 /// Get the path to the settings.json file
 fn settings_path() -> PathBuf {
-    settings_dir().join("settings.json")
+    connection::data_dir().join("settings.json")
 }
 
 /// Load settings from disk
@@ -91,6 +152,46 @@ fn save_settings(settings: &AppSettings) -> Result<(), String> {
     Ok(())
 }
 
+/// Load, modify and save settings in one step (for other modules that own a setting)
+pub fn update_settings<F: FnOnce(&mut AppSettings)>(f: F) -> Result<(), String> {
+    let mut settings = load_settings();
+    f(&mut settings);
+    save_settings(&settings)
+}
+
+/// FCH backend base URL (no trailing slash): settings override, then VITE_API_BASE, then the default
+pub fn api_base() -> String {
+    load_settings()
+        .api_base_override
+        // An override saved before https was required (or restored from a backup) is ignored
+        .filter(|s| check_api_base(s.trim()).is_ok())
+        .or_else(|| std::env::var("VITE_API_BASE").ok().filter(|s| !s.trim().is_empty()))
+        .unwrap_or_else(|| DEFAULT_API_BASE.to_string())
+        .trim()
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Re-apply every setting that modules keep in memory (startup and after a backup restore)
+pub fn reload_all_from_settings() {
+    use crate::modules::log_reader::{event_exporter, join_grace, log_parser, log_reader, rejoin_storm, session_markers};
+    
+    log_parser::reload_log_timezone();
+    session_markers::reload();
+    rejoin_storm::load_config();
+    join_grace::load_config();
+    log_reader::load_config();
+    crate::modules::instance_monitor::batcher::load_config();
+    crate::modules::vrchat_process::vrchat_process::load_config();
+    crate::modules::http_api::http_api::load_config();
+    if let Err(e) = crate::modules::debug::log_file::init_from_settings() {
+        crate::debug_eprintln!("[settings] Failed to apply debug log file setting: {}", e);
+    }
+    if let Err(e) = event_exporter::init_from_settings() {
+        crate::debug_eprintln!("[settings] Failed to apply event export setting: {}", e);
+    }
+}
+
 /// Initialize settings (create default file if needed)
 pub fn init_settings() -> Result<(), String> {
     let path = settings_path();
@@ -116,6 +217,30 @@ pub fn set_master_volume(volume: f64) -> Result<(), String> {
     save_settings(&settings)
 }
 
+/// Mute or unmute all notification sounds
+#[tauri::command]
+pub fn set_notifications_muted(muted: bool) -> Result<(), String> {
+    let mut settings = load_settings();
+    settings.notifications_muted = muted;
+    save_settings(&settings)
+}
+
+/// Enable or disable peak normalization of notification sounds
+#[tauri::command]
+pub fn set_normalize_sounds(enabled: bool) -> Result<(), String> {
+    let mut settings = load_settings();
+    settings.normalize_sounds = enabled;
+    save_settings(&settings)
+}
+
+/// Enable or disable alert suppression for the local user
+#[tauri::command]
+pub fn set_suppress_self_events(enabled: bool) -> Result<(), String> {
+    let mut settings = load_settings();
+    settings.suppress_self_events = Some(enabled);
+    save_settings(&settings)
+}
+
 /// Set group notification settings
 #[tauri::command]
 pub fn set_group_notification_settings(
@@ -141,3 +266,89 @@ pub fn set_local_notification_settings(
     settings.local_notifications.volume = volume;
     save_settings(&settings)
 }
+
+/// Set the timezone used to interpret log timestamps (IANA name, e.g. "Europe/Berlin").
+/// Pass None or an empty string to go back to the system local timezone.
+#[tauri::command]
+pub fn set_log_timezone(timezone: Option<String>) -> Result<(), String> {
+    let timezone = timezone.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    if let Some(ref tz) = timezone {
+        tz.parse::<chrono_tz::Tz>()
+            .map_err(|_| format!("Unknown timezone: {}", tz))?;
+    }
+    let mut settings = load_settings();
+    settings.log_timezone = timezone;
+    save_settings(&settings)?;
+    crate::modules::log_reader::log_parser::reload_log_timezone();
+    Ok(())
+}
+
+/// Only https backends may receive group access tokens; plain http is allowed for a
+/// backend running on this machine (localhost / 127.0.0.1)
+fn check_api_base(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid API base {}: {}", url, e))?;
+    match parsed.scheme() {
+        "https" => Ok(()),
+        "http" if matches!(parsed.host_str(), Some("localhost") | Some("127.0.0.1")) => Ok(()),
+        "http" => Err(format!("API base must use https:// (http:// is only allowed for localhost): {}", url)),
+        _ => Err(format!("API base must start with https://: {}", url)),
+    }
+}
+
+/// Point the app at another backend (e.g. a self-hosted one). Must be an https URL, or
+/// http on localhost. Pass None or an empty string to go back to VITE_API_BASE / the default.
+#[tauri::command]
+pub fn set_api_base_override(url: Option<String>) -> Result<String, String> {
+    let url = url
+        .map(|s| s.trim().trim_end_matches('/').to_string())
+        .filter(|s| !s.is_empty());
+    if let Some(ref u) = url {
+        check_api_base(u)?;
+    }
+    let mut settings = load_settings();
+    settings.api_base_override = url;
+    save_settings(&settings)?;
+    Ok(api_base())
+}
+
+/// Effective backend base URL (so the frontend talks to the same server)
+#[tauri::command]
+pub fn get_api_base() -> Result<String, String> {
+    Ok(api_base())
+}
+
+/// Set the initial log tail size (bytes) the backfill searches for the current instance.
+/// The window still doubles until the instance start is found, so this only tunes speed.
+/// Pass None to restore the default (4MB).
+#[tauri::command]
+pub fn set_backfill_scan_bytes(bytes: Option<u64>) -> Result<(), String> {
+    let mut settings = load_settings();
+    settings.backfill_scan_bytes = bytes.filter(|b| *b > 0);
+    save_settings(&settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_base_allows_https_anywhere() {
+        assert!(check_api_base("https://fch.example.com").is_ok());
+        assert!(check_api_base("https://10.0.0.5:8443/api").is_ok());
+    }
+
+    #[test]
+    fn api_base_allows_plain_http_only_on_loopback() {
+        assert!(check_api_base("http://localhost:3000").is_ok());
+        assert!(check_api_base("http://127.0.0.1:8080").is_ok());
+        assert!(check_api_base("http://fch.example.com").is_err());
+        assert!(check_api_base("http://localhost.example.com").is_err());
+        assert!(check_api_base("http://localhost@fch.example.com").is_err());
+    }
+
+    #[test]
+    fn api_base_rejects_other_schemes_and_garbage() {
+        assert!(check_api_base("ftp://fch.example.com").is_err());
+        assert!(check_api_base("fch.example.com").is_err());
+    }
+}
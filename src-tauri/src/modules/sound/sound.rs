@@ -8,7 +8,72 @@
 // 5. None (no sound played)
 
 use crate::modules::local_db::localdb;
+use crate::modules::log_reader::log_parser;
 use crate::modules::settings::settings;
+use lazy_static::lazy_static;
+use rodio::{OutputStream, OutputStreamHandle, Sink};
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+
+lazy_static! {
+    // Shared output for all notification sounds. OutputStream isn't Send, so it lives on a
+    // dedicated audio thread; only the handle is shared. Dropping the sender next to it
+    // lets that thread exit and close the device (see reset_output).
+    static ref OUTPUT_HANDLE: Mutex<Option<(OutputStreamHandle, Sender<()>)>> = Mutex::new(None);
+
+    // Sinks that may still be playing, so stop_all_sounds can cut them off
+    static ref ACTIVE_SINKS: Mutex<Vec<Sink>> = Mutex::new(Vec::new());
+}
+
+/// Get the shared output handle, opening the audio device on first use (retried if that failed)
+fn output_handle() -> Result<OutputStreamHandle, String> {
+    let mut guard = OUTPUT_HANDLE.lock().map_err(|_| "Failed to lock audio output".to_string())?;
+    if let Some((handle, _)) = guard.as_ref() {
+        return Ok(handle.clone());
+    }
+    
+    let (tx, rx) = std::sync::mpsc::channel();
+    let (keep_tx, keep_rx) = std::sync::mpsc::channel::<()>();
+    std::thread::Builder::new()
+        .name("audio-output".to_string())
+        .spawn(move || match OutputStream::try_default() {
+            Ok((_stream, handle)) => {
+                let _ = tx.send(Ok(handle));
+                // Keep _stream alive until reset_output drops the sender
+                let _ = keep_rx.recv();
+            }
+            Err(e) => {
+                let _ = tx.send(Err(format!("Failed to create audio output stream: {}", e)));
+            }
+        })
+        .map_err(|e| format!("Failed to start audio thread: {}", e))?;
+    
+    let handle = rx
+        .recv()
+        .map_err(|_| "Audio thread exited before opening the output stream".to_string())??;
+    *guard = Some((handle.clone(), keep_tx));
+    Ok(handle)
+}
+
+/// Forget the shared output so the next sound reopens the (possibly changed) default device
+fn reset_output() {
+    if let Ok(mut guard) = OUTPUT_HANDLE.lock() {
+        guard.take();
+    }
+}
+
+/// Create a sink on the shared output. If that fails (e.g. the headset it was opened on was
+/// unplugged), reopen the default device once and try again.
+fn new_sink() -> Result<Sink, String> {
+    match Sink::try_new(&output_handle()?) {
+        Ok(sink) => Ok(sink),
+        Err(e) => {
+            crate::debug_eprintln!("[sound] Audio sink failed ({}), reopening the output device", e);
+            reset_output();
+            Sink::try_new(&output_handle()?).map_err(|e| format!("Failed to create audio sink: {}", e))
+        }
+    }
+}
 
 /// Play sound for a user based on notification type and priority
 #[tauri::command]
@@ -20,6 +85,24 @@ pub fn play_user_notification_sound(
     // Load settings to get volumes and default sounds
     let settings = settings::get_settings().map_err(|e| format!("Failed to load settings: {}", e))?;
     
+    // Never alert for ourselves (e.g. when we're on our own watchlist via a group token)
+    if settings.suppress_self_events.unwrap_or(true) && log_parser::is_self_user(&user_id) {
+        return Ok(());
+    }
+    
+    // Record the alert for the watchlist timeline (even when muted)
+    if has_group_notifications || has_local_notifications {
+        let source = if has_group_notifications { "group" } else { "local" };
+        if let Err(e) = crate::modules::watchlist::watchlist_hits::record_hit(&user_id, source) {
+            crate::debug_eprintln!("[sound] Failed to record watchlist hit: {}", e);
+        }
+    }
+    
+    // Global mute short-circuits before any sound (including the system fallback)
+    if settings.notifications_muted {
+        return Ok(());
+    }
+    
     // Determine which notification type to use
     let (sound_path, volume, is_group) = if has_group_notifications {
         // Group notifications take priority
@@ -46,8 +129,9 @@ pub fn play_user_notification_sound(
     // Play the sound (spawn async task to avoid blocking)
     let sound_path_clone = sound_path.clone();
     let is_group_clone = is_group;
+    let normalize = settings.normalize_sounds;
     tauri::async_runtime::spawn(async move {
-        if let Err(e) = play_sound_file(&sound_path_clone, volume).await {
+        if let Err(e) = play_sound_file(&sound_path_clone, volume, normalize).await {
             crate::debug_eprintln!("[sound] Failed to play sound: {}", e);
             // If custom sound fails, fall back to system sound
             play_windows_system_sound(is_group_clone);
@@ -62,27 +146,71 @@ fn get_sound_path_for_user(
     user_id: &str,
     default_sound: &Option<String>,
 ) -> Result<Option<String>, String> {
-    // First, check for custom sound in local_db
-    match localdb::get_user_sound(user_id.to_string()) {
-        Ok(value) => {
-            // value is serde_json::Value, check for soundPath field
-            if let Some(obj) = value.as_object() {
-                if let Some(sound_path_val) = obj.get("soundPath") {
-                    if let Some(sound_path) = sound_path_val.as_str() {
-                        if !sound_path.is_empty() {
-                            return Ok(Some(sound_path.to_string()));
-                        }
-                    }
-                }
-            }
-        }
-        Err(_) => {
-            // User might not have a custom sound, continue to default
-        }
+    // First, check for custom sound in local_db, then fall back to default sound
+    Ok(custom_sound_for_user(user_id).or_else(|| default_sound.clone()))
+}
+
+/// Custom sound set on the user's local note, if any
+fn custom_sound_for_user(user_id: &str) -> Option<String> {
+    // User might not have a custom sound (or notes may fail to load): no custom sound then
+    let value = localdb::get_user_sound(user_id.to_string()).ok()?;
+    value
+        .get("soundPath")
+        .and_then(|v| v.as_str())
+        .filter(|path| !path.is_empty())
+        .map(|path| path.to_string())
+}
+
+/// Describe one notification kind as playback resolves it (default sound or system fallback)
+fn describe_kind(kind: &settings::NotificationSettings, master_volume: f64) -> serde_json::Value {
+    let path = kind.default_sound_path.clone();
+    serde_json::json!({
+        "defaultSoundPath": path,
+        "defaultSoundExists": path.as_deref().map(|p| std::path::Path::new(p).is_file()),
+        "volume": kind.volume,
+        "effectiveVolume": master_volume * kind.volume,
+        "source": if path.is_some() { "default" } else { "system" }
+    })
+}
+
+/// Resolved notification configuration, exactly as play_user_notification_sound applies it.
+/// All values come from settings.json (modules/settings); per-user sounds come from notes.
+/// Pass user_id to also see which sound that user's alerts would use and whether they'd be skipped.
+#[tauri::command]
+pub fn get_effective_notification_config(user_id: Option<String>) -> Result<serde_json::Value, String> {
+    let settings = settings::get_settings().map_err(|e| format!("Failed to load settings: {}", e))?;
+    let suppress_self = settings.suppress_self_events.unwrap_or(true);
+    
+    let mut config = serde_json::json!({
+        "precedence": ["custom user sound", "group/local default sound", "Windows system sound"],
+        "masterVolume": settings.master_volume,
+        "muted": settings.notifications_muted,
+        "normalize": settings.normalize_sounds,
+        "suppressSelfEvents": suppress_self,
+        "group": describe_kind(&settings.group_notifications, settings.master_volume),
+        "local": describe_kind(&settings.local_notifications, settings.master_volume)
+    });
+    
+    if let Some(user_id) = user_id.map(|id| id.trim().to_string()).filter(|id| !id.is_empty()) {
+        let custom = custom_sound_for_user(&user_id);
+        let is_self = log_parser::is_self_user(&user_id);
+        let resolve = |default: &Option<String>| match (&custom, default) {
+            (Some(path), _) => serde_json::json!({ "soundPath": path, "source": "custom" }),
+            (None, Some(path)) => serde_json::json!({ "soundPath": path, "source": "default" }),
+            (None, None) => serde_json::json!({ "soundPath": null, "source": "system" }),
+        };
+        config["user"] = serde_json::json!({
+            "userId": user_id,
+            "isSelf": is_self,
+            "suppressed": suppress_self && is_self,
+            "customSoundPath": custom,
+            "customSoundExists": custom.as_deref().map(|p| std::path::Path::new(p).is_file()),
+            "group": resolve(&settings.group_notifications.default_sound_path),
+            "local": resolve(&settings.local_notifications.default_sound_path)
+        });
     }
     
-    // Fall back to default sound
-    Ok(default_sound.clone())
+    Ok(config)
 }
 
 /// Play Windows system sound as fallback
@@ -108,17 +236,20 @@ fn play_windows_system_sound(is_group: bool) {
 }
 
 /// Preview group notification sound (for testing in settings)
+/// volume overrides the saved group volume for this playback only (e.g. while dragging the slider)
 #[tauri::command]
-pub fn preview_group_notification_sound() -> Result<(), String> {
+pub fn preview_group_notification_sound(volume: Option<f64>) -> Result<(), String> {
     let settings = settings::get_settings().map_err(|e| format!("Failed to load settings: {}", e))?;
     
     let sound_path = settings.group_notifications.default_sound_path.clone();
-    let volume = settings.master_volume * settings.group_notifications.volume;
+    let kind_volume = volume.map(|v| v.max(0.0).min(1.0)).unwrap_or(settings.group_notifications.volume);
+    let volume = settings.master_volume * kind_volume;
     
+    let normalize = settings.normalize_sounds;
     if let Some(path) = sound_path {
         let path_clone = path.clone();
         tauri::async_runtime::spawn(async move {
-            if let Err(_) = play_sound_file(&path_clone, volume).await {
+            if let Err(_) = play_sound_file(&path_clone, volume, normalize).await {
                 // If custom sound fails, fall back to system sound
                 play_windows_system_sound(true);
             }
@@ -132,17 +263,20 @@ pub fn preview_group_notification_sound() -> Result<(), String> {
 }
 
 /// Preview local notification sound (for testing in settings)
+/// volume overrides the saved local volume for this playback only (e.g. while dragging the slider)
 #[tauri::command]
-pub fn preview_local_notification_sound() -> Result<(), String> {
+pub fn preview_local_notification_sound(volume: Option<f64>) -> Result<(), String> {
     let settings = settings::get_settings().map_err(|e| format!("Failed to load settings: {}", e))?;
     
     let sound_path = settings.local_notifications.default_sound_path.clone();
-    let volume = settings.master_volume * settings.local_notifications.volume;
+    let kind_volume = volume.map(|v| v.max(0.0).min(1.0)).unwrap_or(settings.local_notifications.volume);
+    let volume = settings.master_volume * kind_volume;
     
+    let normalize = settings.normalize_sounds;
     if let Some(path) = sound_path {
         let path_clone = path.clone();
         tauri::async_runtime::spawn(async move {
-            if let Err(_) = play_sound_file(&path_clone, volume).await {
+            if let Err(_) = play_sound_file(&path_clone, volume, normalize).await {
                 // If custom sound fails, fall back to system sound
                 play_windows_system_sound(false);
             }
@@ -155,32 +289,120 @@ pub fn preview_local_notification_sound() -> Result<(), String> {
     Ok(())
 }
 
+/// Files larger than this are never normalized (decoding them up front would delay the alert)
+const NORMALIZE_MAX_FILE_BYTES: u64 = 2 * 1024 * 1024;
+/// Peak level normalized sounds are scaled to
+const NORMALIZE_TARGET_PEAK: f32 = 0.9;
+/// Cap on the boost so near-silent files don't turn into loud noise
+const NORMALIZE_MAX_GAIN: f32 = 4.0;
+
+/// Gain that brings the sample peak to NORMALIZE_TARGET_PEAK
+fn normalization_gain(samples: &[f32]) -> f32 {
+    let peak = samples.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+    if peak <= f32::EPSILON {
+        return 1.0;
+    }
+    (NORMALIZE_TARGET_PEAK / peak).min(NORMALIZE_MAX_GAIN)
+}
+
 /// Play a sound file with specified volume
-async fn play_sound_file(path: &str, volume: f64) -> Result<(), String> {
-    use rodio::{Decoder, OutputStream, Sink};
+/// Returns once playback has started; overlapping sounds are mixed on the shared stream.
+/// With normalize, short files are fully decoded first to measure their peak (adds a few ms).
+async fn play_sound_file(path: &str, volume: f64, normalize: bool) -> Result<(), String> {
+    use rodio::buffer::SamplesBuffer;
+    use rodio::{Decoder, Source};
     use std::fs::File;
     use std::io::BufReader;
     
-    // Get output stream (keep it alive)
-    let (_stream, stream_handle) = OutputStream::try_default()
-        .map_err(|e| format!("Failed to create audio output stream: {}", e))?;
-    
     // Open file
     let file = File::open(path)
         .map_err(|e| format!("Failed to open sound file {}: {}", path, e))?;
+    let file_size = file.metadata().map(|m| m.len()).unwrap_or(u64::MAX);
     
     let source = Decoder::new(BufReader::new(file))
         .map_err(|e| format!("Failed to decode sound file {}: {}", path, e))?;
     
     // Create sink and set volume
-    let sink = Sink::try_new(&stream_handle)
-        .map_err(|e| format!("Failed to create audio sink: {}", e))?;
+    let sink = new_sink()?;
+    
+    if normalize && file_size <= NORMALIZE_MAX_FILE_BYTES {
+        let channels = source.channels();
+        let sample_rate = source.sample_rate();
+        let samples: Vec<f32> = source.convert_samples().collect();
+        sink.set_volume(volume as f32 * normalization_gain(&samples));
+        sink.append(SamplesBuffer::new(channels, sample_rate, samples));
+    } else {
+        sink.set_volume(volume as f32);
+        sink.append(source);
+    }
+    
+    // Let it play out on the shared stream without holding this task; keep it for stop_all_sounds
+    if let Ok(mut sinks) = ACTIVE_SINKS.lock() {
+        sinks.retain(|s| !s.empty());
+        sinks.push(sink);
+    } else {
+        sink.detach();
+    }
+    
+    Ok(())
+}
+
+/// Check that a sound file can be opened and decoded (call when the user picks a file)
+/// Returns { valid, durationSecs, channels, sampleRate }; durationSecs is null if it can't be determined
+#[tauri::command]
+pub fn validate_sound_file(path: String) -> Result<serde_json::Value, String> {
+    use rodio::{Decoder, Source};
+    use std::fs::File;
+    use std::io::BufReader;
+    
+    let file = File::open(&path)
+        .map_err(|e| format!("Failed to open sound file {}: {}", path, e))?;
+    let file_size = file.metadata().map(|m| m.len()).unwrap_or(u64::MAX);
+    let mut source = Decoder::new(BufReader::new(file))
+        .map_err(|e| format!("Failed to decode sound file {}: {}", path, e))?;
+    
+    let channels = source.channels();
+    let sample_rate = source.sample_rate();
+    let mut duration_secs = source.total_duration().map(|d| d.as_secs_f64());
+    
+    // Make sure at least one frame actually decodes
+    if source.next().is_none() {
+        return Err(format!("Sound file {} contains no audio", path));
+    }
     
-    sink.set_volume(volume as f32);
-    sink.append(source);
+    // Some formats (e.g. mp3) don't report a duration; count samples for small files
+    if duration_secs.is_none() && file_size <= NORMALIZE_MAX_FILE_BYTES && channels > 0 && sample_rate > 0 {
+        let samples = 1 + source.count();
+        duration_secs = Some(samples as f64 / (channels as f64 * sample_rate as f64));
+    }
     
-    // Wait for playback to finish
-    sink.sleep_until_end();
+    Ok(serde_json::json!({
+        "valid": true,
+        "durationSecs": duration_secs,
+        "channels": channels,
+        "sampleRate": sample_rate
+    }))
+}
+
+/// Stop every notification sound that is currently playing
+#[tauri::command]
+pub fn stop_all_sounds() -> Result<(), String> {
+    let sinks: Vec<Sink> = ACTIVE_SINKS
+        .lock()
+        .map_err(|_| "Failed to lock active sounds".to_string())?
+        .drain(..)
+        .collect();
+    for sink in sinks {
+        sink.stop();
+    }
+    
+    // Also cut off an async Windows system sound fallback
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use windows::core::PCWSTR;
+        use windows::Win32::Media::Audio::{PlaySoundW, SND_ASYNC};
+        let _ = PlaySoundW(PCWSTR::null(), None, SND_ASYNC);
+    }
     
     Ok(())
 }
@@ -0,0 +1,2 @@
+// Tray module - system tray icon for running the app in the background
+pub mod tray;
@@ -0,0 +1,104 @@
+// Tray: System tray icon with quick toggles
+//
+// Menu: mute notifications, start/stop the log watcher, show/hide the window, quit.
+// Left-clicking the icon restores the main window. Changes made here are emitted
+// (notifications_muted_changed, watcher_state_changed) so open pages can update.
+
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{App, AppHandle, Emitter, Manager};
+
+use crate::modules::log_reader::log_reader;
+use crate::modules::notifications::toast::focus_main_window;
+use crate::modules::settings::settings;
+use crate::modules::window_state::window_state;
+
+/// Create the tray icon (called once from setup)
+pub fn init_tray(app: &App) -> Result<(), String> {
+    let muted = settings::get_settings().map(|s| s.notifications_muted).unwrap_or(false);
+    
+    let mute_item = CheckMenuItem::with_id(app, "toggle_mute", "Mute notifications", true, muted, None::<&str>)
+        .map_err(|e| format!("Failed to create tray menu: {}", e))?;
+    let watcher_item = MenuItem::with_id(app, "toggle_watcher", "Start/stop log watcher", true, None::<&str>)
+        .map_err(|e| format!("Failed to create tray menu: {}", e))?;
+    let window_item = MenuItem::with_id(app, "toggle_window", "Show/hide window", true, None::<&str>)
+        .map_err(|e| format!("Failed to create tray menu: {}", e))?;
+    let separator = PredefinedMenuItem::separator(app)
+        .map_err(|e| format!("Failed to create tray menu: {}", e))?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)
+        .map_err(|e| format!("Failed to create tray menu: {}", e))?;
+    let menu = Menu::with_items(app, &[&mute_item, &watcher_item, &window_item, &separator, &quit_item])
+        .map_err(|e| format!("Failed to create tray menu: {}", e))?;
+    
+    let mut builder = TrayIconBuilder::with_id("main")
+        .tooltip("FCH App")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(move |app, event| match event.id().as_ref() {
+            "toggle_mute" => toggle_mute(app, &mute_item),
+            "toggle_watcher" => toggle_watcher(app),
+            "toggle_window" => toggle_window(app),
+            "quit" => {
+                // Quitting from the tray skips CloseRequested, so save the window geometry here
+                let _ = window_state::save_now();
+                app.exit(0);
+            }
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                focus_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder
+        .build(app)
+        .map_err(|e| format!("Failed to create tray icon: {}", e))?;
+    
+    Ok(())
+}
+
+/// Flip notifications_muted and keep the check mark in sync with the saved value
+fn toggle_mute(app: &AppHandle, item: &CheckMenuItem<tauri::Wry>) {
+    let muted = !settings::get_settings().map(|s| s.notifications_muted).unwrap_or(false);
+    if let Err(e) = settings::set_notifications_muted(muted) {
+        crate::debug_eprintln!("[tray] Failed to toggle mute: {}", e);
+        return;
+    }
+    let _ = item.set_checked(muted);
+    let _ = app.emit("notifications_muted_changed", serde_json::json!({ "muted": muted }));
+}
+
+/// Start the log watcher if it's stopped, otherwise stop it
+fn toggle_watcher(app: &AppHandle) {
+    let result = if log_reader::is_log_reader_running() {
+        log_reader::stop_log_reader()
+    } else {
+        log_reader::start_log_reader(app.clone())
+    };
+    if let Err(e) = result {
+        crate::debug_eprintln!("[tray] Failed to toggle log watcher: {}", e);
+    }
+    let _ = app.emit("watcher_state_changed", serde_json::json!({
+        "running": log_reader::is_log_reader_running()
+    }));
+}
+
+/// Hide the main window if it's visible, otherwise restore and focus it
+fn toggle_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false) {
+        let _ = window.hide();
+    } else {
+        focus_main_window(app);
+    }
+}
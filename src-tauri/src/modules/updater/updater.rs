@@ -4,15 +4,38 @@
 // 1. Checking GitHub releases for new versions
 // 2. Downloading the setup.exe installer
 // 3. Running the installer (with elevation on Windows)
+// 4. Keeping the last few installers so a broken release can be rolled back
 
+use std::io::Write;
 use std::path::PathBuf;
 use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
 use serde::{Deserialize, Serialize};
 
+use crate::modules::app_state::app_state;
+use crate::modules::database::connection;
+use crate::modules::http_client::http_client;
+
 const GITHUB_REPO: &str = "Lumi-VRC/FCH-Toolkit-App";
 const GITHUB_API_BASE: &str = "https://api.github.com/repos";
 
+/// Set by cancel_update_download; checked between downloaded chunks
+static DOWNLOAD_CANCELLED: AtomicBool = AtomicBool::new(false);
+/// True while download_update is running
+static DOWNLOAD_ACTIVE: AtomicBool = AtomicBool::new(false);
+/// Installers kept in the data folder for rollback
+const KEPT_INSTALLERS: usize = 3;
+/// app_state keys for version tracking / rollback
+const LAST_RUN_VERSION_KEY: &str = "last_run_version";
+const HIGHEST_RUN_VERSION_KEY: &str = "highest_run_version";
+const LAST_INSTALLER_KEY: &str = "last_installer";
+/// A download that receives nothing for this long is treated as stalled
+const DOWNLOAD_STALL_TIMEOUT: Duration = Duration::from_secs(60);
+/// Minimum time between updater:download-progress events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
 #[derive(Debug, Serialize, Deserialize)]
 struct GitHubRelease {
     tag_name: String,
@@ -39,11 +62,12 @@ pub struct UpdateInfo {
 async fn fetch_latest_release() -> Result<GitHubRelease, String> {
     let url = format!("{}/{}/releases/latest", GITHUB_API_BASE, GITHUB_REPO);
     
-    let client = reqwest::Client::new();
+    let client = http_client::client();
     let response = client
         .get(&url)
         .header("Accept", "application/vnd.github+json")
         .header("User-Agent", "FCH-App-Updater")
+        .timeout(http_client::API_TIMEOUT)
         .send()
         .await
         .map_err(|e| format!("Failed to fetch release: {}", e))?;
@@ -94,10 +118,56 @@ fn compare_versions(local: &str, remote: &str) -> i32 {
     0
 }
 
-/// Check if an update is available
+/// Version of this build, baked in at compile time (the backend no longer needs the frontend to tell it)
+pub fn app_version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// Version of the running backend
+#[tauri::command]
+pub fn get_app_version() -> Result<String, String> {
+    Ok(app_version().to_string())
+}
+
+/// Remember the running version (called once at startup). The highest version ever run is kept
+/// so a downgrade (rollback or manual reinstall of an older build) can be detected.
+pub fn record_running_version(running: &str) {
+    let running = normalize_version(running);
+    let _ = app_state::set(LAST_RUN_VERSION_KEY, Some(&running));
+    let highest = app_state::get(HIGHEST_RUN_VERSION_KEY);
+    if highest.as_deref().map_or(true, |h| compare_versions(h, &running) > 0) {
+        let _ = app_state::set(HIGHEST_RUN_VERSION_KEY, Some(&running));
+    }
+}
+
+/// { running, highestRun, downgraded } for the given running version
+fn version_status(running: &str) -> serde_json::Value {
+    let running = normalize_version(running);
+    let highest = app_state::get(HIGHEST_RUN_VERSION_KEY);
+    let downgraded = highest.as_deref().map_or(false, |h| compare_versions(&running, h) > 0);
+    serde_json::json!({
+        "running": running,
+        "highestRun": highest,
+        "downgraded": downgraded
+    })
+}
+
+/// Whether this build is older than a version that already ran on this machine
 #[tauri::command]
-pub async fn check_for_update(local_version: String) -> Result<Option<UpdateInfo>, String> {
-    let local_v = normalize_version(&local_version);
+pub fn get_version_status() -> Result<serde_json::Value, String> {
+    Ok(version_status(app_version()))
+}
+
+/// Check if an update is available (local_version defaults to this build's own version)
+/// Also emits updater:downgrade-detected when the running build is older than one that ran before.
+#[tauri::command]
+pub async fn check_for_update(app_handle: AppHandle, local_version: Option<String>) -> Result<Option<UpdateInfo>, String> {
+    let local_v = normalize_version(local_version.as_deref().unwrap_or(app_version()));
+    
+    let status = version_status(&local_v);
+    if status["downgraded"].as_bool().unwrap_or(false) {
+        let _ = app_handle.emit("updater:downgrade-detected", status);
+    }
     
     let release = fetch_latest_release().await?;
     let remote_v = normalize_version(&release.tag_name);
@@ -152,22 +222,45 @@ fn get_downloads_dir() -> Result<PathBuf, String> {
         .map(|p| p.to_path_buf())
 }
 
-/// Download the setup.exe file
+/// Download the setup.exe file.
+/// Streams to <filename>.part (emitting updater:download-progress) and renames it when complete,
+/// so a cancelled or failed download never leaves a truncated installer behind.
 #[tauri::command]
-pub async fn download_update(download_url: String, filename: String) -> Result<String, String> {
-    let downloads_dir = get_downloads_dir()?;
-    let file_path = downloads_dir.join(&filename);
+pub async fn download_update(app_handle: AppHandle, download_url: String, filename: String) -> Result<String, String> {
+    if DOWNLOAD_ACTIVE.swap(true, Ordering::SeqCst) {
+        return Err("An update download is already in progress".to_string());
+    }
+    DOWNLOAD_CANCELLED.store(false, Ordering::SeqCst);
     
+    let downloads_dir = get_downloads_dir();
+    let result = match downloads_dir {
+        Ok(dir) => stream_download(&app_handle, &download_url, dir.join(&filename)).await,
+        Err(e) => Err(e),
+    };
+    DOWNLOAD_ACTIVE.store(false, Ordering::SeqCst);
+    let file_path = result?;
+    
+    // Return the file path as string
+    file_path
+        .to_str()
+        .ok_or_else(|| "Invalid file path".to_string())
+        .map(|s| s.to_string())
+}
+
+async fn stream_download(app_handle: &AppHandle, download_url: &str, file_path: PathBuf) -> Result<PathBuf, String> {
     // Create downloads directory if it doesn't exist
     if let Some(parent) = file_path.parent() {
         fs::create_dir_all(parent)
             .map_err(|e| format!("Failed to create downloads directory: {}", e))?;
     }
+    let mut partial_path = file_path.clone().into_os_string();
+    partial_path.push(".part");
+    let partial_path = PathBuf::from(partial_path);
     
-    // Download the file
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&download_url)
+    // Download the file (no total timeout: large installers on slow connections take a while)
+    let client = http_client::client();
+    let mut response = client
+        .get(download_url)
         .header("User-Agent", "FCH-App-Updater")
         .send()
         .await
@@ -177,25 +270,165 @@ pub async fn download_update(download_url: String, filename: String) -> Result<S
         return Err(format!("Download failed with status: {}", response.status()));
     }
     
-    let bytes = response
-        .bytes()
-        .await
-        .map_err(|e| format!("Failed to read download: {}", e))?;
+    let total = response.content_length();
+    let mut file = fs::File::create(&partial_path)
+        .map_err(|e| format!("Failed to create file: {}", e))?;
+    let mut downloaded: u64 = 0;
+    let mut last_progress = Instant::now();
+    
+    let outcome: Result<(), String> = loop {
+        if DOWNLOAD_CANCELLED.load(Ordering::SeqCst) {
+            break Err("Download cancelled".to_string());
+        }
+        let chunk = match tokio::time::timeout(DOWNLOAD_STALL_TIMEOUT, response.chunk()).await {
+            Ok(Ok(Some(chunk))) => chunk,
+            Ok(Ok(None)) => break Ok(()),
+            Ok(Err(e)) => break Err(format!("Failed to read download: {}", e)),
+            Err(_) => break Err("Download stalled (no data received for 60s)".to_string()),
+        };
+        if let Err(e) = file.write_all(&chunk) {
+            break Err(format!("Failed to save file: {}", e));
+        }
+        downloaded += chunk.len() as u64;
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            let _ = app_handle.emit("updater:download-progress", serde_json::json!({
+                "downloaded": downloaded,
+                "total": total
+            }));
+        }
+    };
+    drop(file);
+    
+    if let Err(e) = outcome {
+        let _ = fs::remove_file(&partial_path);
+        if DOWNLOAD_CANCELLED.load(Ordering::SeqCst) {
+            let _ = app_handle.emit("updater:download-cancelled", ());
+        }
+        return Err(e);
+    }
     
-    // Write to file
-    fs::write(&file_path, bytes)
+    let _ = app_handle.emit("updater:download-progress", serde_json::json!({
+        "downloaded": downloaded,
+        "total": total
+    }));
+    fs::rename(&partial_path, &file_path)
         .map_err(|e| format!("Failed to save file: {}", e))?;
+    Ok(file_path)
+}
+
+/// Cancel the running update download (its partial file is removed).
+/// Returns false when no download was in progress.
+#[tauri::command]
+pub fn cancel_update_download() -> Result<bool, String> {
+    if !DOWNLOAD_ACTIVE.load(Ordering::SeqCst) {
+        return Ok(false);
+    }
+    DOWNLOAD_CANCELLED.store(true, Ordering::SeqCst);
+    Ok(true)
+}
+
+/// Folder in the app data directory where recent installers are kept
+fn installers_dir() -> PathBuf {
+    connection::data_dir().join("installers")
+}
+
+/// Kept installers, newest first (by when they were stored)
+fn kept_installers() -> Vec<(PathBuf, std::time::SystemTime)> {
+    let mut installers: Vec<(PathBuf, std::time::SystemTime)> = fs::read_dir(installers_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().extension().map_or(false, |ext| ext.eq_ignore_ascii_case("exe")))
+                .filter_map(|e| {
+                    let modified = e.metadata().and_then(|m| m.modified()).ok()?;
+                    Some((e.path(), modified))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    installers.sort_by(|a, b| b.1.cmp(&a.1));
+    installers
+}
+
+/// Copy an installer into the rollback folder (pruning old ones) and mark it as the last run.
+/// Installers already in the folder (rollbacks) are only marked.
+fn keep_installer(installer_path: &str) -> Result<(), String> {
+    let source = PathBuf::from(installer_path);
+    let dir = installers_dir();
+    let file_name = source
+        .file_name()
+        .ok_or_else(|| "Invalid installer path".to_string())?
+        .to_owned();
     
-    // Return the file path as string
-    file_path
-        .to_str()
-        .ok_or_else(|| "Invalid file path".to_string())
-        .map(|s| s.to_string())
+    if source.parent() != Some(dir.as_path()) {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create installers folder: {}", e))?;
+        let kept = dir.join(&file_name);
+        fs::copy(&source, &kept).map_err(|e| format!("Failed to keep installer: {}", e))?;
+        // fs::copy keeps the source's mtime on Windows; stamp the copy so kept_installers
+        // orders it by when it was stored, not when it was downloaded
+        fs::File::options()
+            .write(true)
+            .open(&kept)
+            .and_then(|f| f.set_modified(std::time::SystemTime::now()))
+            .map_err(|e| format!("Failed to timestamp kept installer: {}", e))?;
+        for (old, _) in kept_installers().into_iter().skip(KEPT_INSTALLERS) {
+            let _ = fs::remove_file(old);
+        }
+    }
+    app_state::set(LAST_INSTALLER_KEY, Some(&file_name.to_string_lossy()))
+}
+
+/// Installers that can be re-run: [{ filename, path, sizeBytes, savedAt, lastRun }], newest first
+#[tauri::command]
+pub fn list_available_rollbacks() -> Result<Vec<serde_json::Value>, String> {
+    let last = app_state::get(LAST_INSTALLER_KEY);
+    Ok(kept_installers()
+        .into_iter()
+        .map(|(path, modified)| {
+            let filename = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            let saved_at = chrono::DateTime::<chrono::Local>::from(modified)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string();
+            serde_json::json!({
+                "lastRun": last.as_deref() == Some(filename.as_str()),
+                "filename": filename,
+                "path": path.to_string_lossy(),
+                "sizeBytes": size,
+                "savedAt": saved_at
+            })
+        })
+        .collect())
+}
+
+/// Re-run the newest kept installer that is older than the last one run (with elevation).
+/// Returns the installer path.
+#[tauri::command]
+pub async fn rollback_to_previous(app_handle: AppHandle) -> Result<String, String> {
+    let installers = kept_installers();
+    let last = app_state::get(LAST_INSTALLER_KEY);
+    let last_index = installers.iter().position(|(path, _)| {
+        path.file_name().map(|n| n.to_string_lossy().to_string()) == last
+    });
+    // Everything after the last-run installer is older; without a marker the newest is the current one
+    let previous = installers
+        .get(last_index.map_or(1, |i| i + 1))
+        .map(|(path, _)| path.to_string_lossy().to_string())
+        .ok_or_else(|| "No previous installer is available to roll back to".to_string())?;
+    
+    run_installer(app_handle, previous.clone()).await?;
+    Ok(previous)
 }
 
 /// Run the installer (with elevation on Windows)
 #[tauri::command]
 pub async fn run_installer(app_handle: AppHandle, installer_path: String) -> Result<(), String> {
+    // Keep a copy for rollback; a failure here must not block the update itself
+    if let Err(e) = keep_installer(&installer_path) {
+        crate::debug_eprintln!("[Updater] {}", e);
+    }
+    
     #[cfg(target_os = "windows")]
     {
         use std::process::Command;
@@ -251,7 +484,7 @@ pub async fn download_and_install_update(
     filename: String,
 ) -> Result<String, String> {
     // Download the file
-    let installer_path = download_update(download_url, filename).await?;
+    let installer_path = download_update(app_handle.clone(), download_url, filename).await?;
     
     // Run the installer
     run_installer(app_handle, installer_path.clone()).await?;
@@ -0,0 +1,2 @@
+// VRChat Process module - detects whether the game is running
+pub mod vrchat_process;
@@ -0,0 +1,124 @@
+// VRChat Process: Detect the game and optionally follow it with the log reader
+//
+// is_vrchat_running takes a Toolhelp process snapshot (a few ms), so it is cheap
+// enough to poll. With auto_start_watcher_when_vrchat_runs on, a background thread
+// checks every POLL_INTERVAL and starts the log reader when VRChat launches and
+// stops it when VRChat exits. Outside Windows the game is never detected.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::modules::log_reader::log_reader;
+use crate::modules::settings::settings;
+
+/// Executable name of the game (compared case-insensitively)
+const VRCHAT_EXE: &str = "VRChat.exe";
+/// How often the auto-start thread checks the process list
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Follow VRChat with the log reader (from settings, see set_auto_start_watcher)
+static AUTO_START: AtomicBool = AtomicBool::new(false);
+/// Whether the monitor thread has been started (only one per app run)
+static MONITOR_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// True if a process named VRChat.exe is running
+#[cfg(target_os = "windows")]
+fn vrchat_process_exists() -> bool {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+    };
+    
+    unsafe {
+        let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else {
+            return false;
+        };
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut found = false;
+        let mut more = Process32FirstW(snapshot, &mut entry).is_ok();
+        while more {
+            let len = entry.szExeFile.iter().position(|c| *c == 0).unwrap_or(entry.szExeFile.len());
+            if String::from_utf16_lossy(&entry.szExeFile[..len]).eq_ignore_ascii_case(VRCHAT_EXE) {
+                found = true;
+                break;
+            }
+            more = Process32NextW(snapshot, &mut entry).is_ok();
+        }
+        let _ = CloseHandle(snapshot);
+        found
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn vrchat_process_exists() -> bool {
+    false
+}
+
+/// Whether VRChat is currently running
+#[tauri::command]
+pub fn is_vrchat_running() -> Result<bool, String> {
+    Ok(vrchat_process_exists())
+}
+
+/// Load the auto-start flag from settings (startup and after a backup restore)
+pub fn load_config() {
+    if let Ok(s) = settings::get_settings() {
+        AUTO_START.store(s.auto_start_watcher_when_vrchat_runs, Ordering::SeqCst);
+    }
+}
+
+/// Start the background monitor (called once from setup). It idles while auto-start is off.
+pub fn init(app_handle: AppHandle) {
+    load_config();
+    if MONITOR_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let spawned = std::thread::Builder::new()
+        .name("vrchat-process".to_string())
+        .spawn(move || {
+            // None until the first check after auto-start is turned on, so the current
+            // state is applied once rather than only reacting to later changes
+            let mut last_running: Option<bool> = None;
+            loop {
+                if AUTO_START.load(Ordering::SeqCst) {
+                    let running = vrchat_process_exists();
+                    if last_running != Some(running) {
+                        let _ = app_handle.emit("vrchat_process_changed", serde_json::json!({ "running": running }));
+                        let result = if running {
+                            log_reader::start_log_reader(app_handle.clone())
+                        } else if last_running.is_some() {
+                            // Only stop on an actual exit, not because VRChat wasn't open yet
+                            log_reader::stop_log_reader()
+                        } else {
+                            Ok(())
+                        };
+                        if let Err(e) = result {
+                            crate::debug_eprintln!("[vrchat_process] Failed to follow VRChat: {}", e);
+                        }
+                        last_running = Some(running);
+                    }
+                } else {
+                    last_running = None;
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        });
+    if let Err(e) = spawned {
+        MONITOR_STARTED.store(false, Ordering::SeqCst);
+        crate::debug_eprintln!("[vrchat_process] Failed to start monitor thread: {}", e);
+    }
+}
+
+/// Start the log reader when VRChat launches and stop it when VRChat exits
+#[tauri::command]
+pub fn set_auto_start_watcher(enabled: bool) -> Result<(), String> {
+    settings::update_settings(|s| {
+        s.auto_start_watcher_when_vrchat_runs = enabled;
+    })?;
+    AUTO_START.store(enabled, Ordering::SeqCst);
+    Ok(())
+}
@@ -0,0 +1,2 @@
+// Watchlist module - history of watchlisted users showing up
+pub mod watchlist_hits;
@@ -0,0 +1,90 @@
+// Watchlist Hits: Timeline of watch alerts
+//
+// Every time a watch alert fires (local watchlist or group match) a row is stored
+// in fchapp.db, so "when did this flagged user last appear?" is a simple query.
+
+use rusqlite::{Connection, Result as SqlResult};
+use crate::modules::database::connection::app_db;
+use serde::{Deserialize, Serialize};
+
+use crate::modules::local_db::localdb;
+use crate::modules::log_reader::log_parser;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WatchlistHit {
+    pub id: i64,
+    pub user_id: String,
+    pub username: Option<String>,
+    pub timestamp: String, // YYYY.MM.DD HH:MM:SS
+    pub source: String,    // "local" or "group"
+}
+
+/// Get or create database connection
+fn get_connection() -> SqlResult<Connection> {
+    let conn = app_db("fchapp.db")?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS watchlist_hits (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id TEXT NOT NULL,
+            username TEXT,
+            ts TEXT NOT NULL,
+            source TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_watchlist_hits_user ON watchlist_hits(user_id, ts DESC)",
+        [],
+    )?;
+    
+    Ok(conn)
+}
+
+/// Initialize the table
+pub fn init_db() -> Result<(), String> {
+    get_connection().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Record a watch alert. source: "local" or "group". Username comes from notes.json if known.
+pub fn record_hit(user_id: &str, source: &str) -> Result<(), String> {
+    let username = localdb::load_all_notes().usernames.get(user_id).cloned();
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO watchlist_hits (user_id, username, ts, source) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![user_id, username, log_parser::now_log_ts(), source],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// List watch alerts, newest first, optionally for one user
+#[tauri::command]
+pub fn list_watchlist_hits(limit: Option<i64>, user_id: Option<String>) -> Result<Vec<WatchlistHit>, String> {
+    let limit = limit.unwrap_or(100).clamp(1, 10_000);
+    let user_id = user_id.filter(|u| !u.trim().is_empty());
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, user_id, username, ts, source FROM watchlist_hits
+             WHERE ?1 IS NULL OR user_id = ?1
+             ORDER BY ts DESC, id DESC LIMIT ?2"
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let hits = stmt
+        .query_map(rusqlite::params![user_id, limit], |row| {
+            Ok(WatchlistHit {
+                id: row.get(0)?,
+                user_id: row.get(1)?,
+                username: row.get(2)?,
+                timestamp: row.get(3)?,
+                source: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(hits)
+}
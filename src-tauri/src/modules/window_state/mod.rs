@@ -0,0 +1,2 @@
+// Window State module - remembers the main window's size and position
+pub mod window_state;
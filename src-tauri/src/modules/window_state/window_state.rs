@@ -0,0 +1,140 @@
+// Window State: Persist and restore the main window geometry
+//
+// Geometry is tracked in memory on move/resize and written to window_state.json
+// when the window closes (or the app quits from the tray). On startup the saved
+// geometry is applied before the window is shown; a position that no longer lands
+// on any connected monitor is dropped and the window is centered instead.
+
+use std::sync::Mutex;
+use std::{fs, path::PathBuf};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use tauri::{App, Manager, PhysicalPosition, PhysicalSize, Runtime, Window, WindowEvent};
+use crate::modules::database::connection;
+
+const MAIN_WINDOW: &str = "main";
+/// How much of the window (px, each axis) must overlap a monitor to count as on-screen
+const MIN_VISIBLE_PX: i32 = 50;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+struct WindowGeometry {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    #[serde(default)]
+    maximized: bool,
+}
+
+lazy_static! {
+    static ref CURRENT: Mutex<Option<WindowGeometry>> = Mutex::new(None);
+}
+
+/// Get the path to the window_state.json file
+fn state_path() -> PathBuf {
+    connection::data_dir().join("window_state.json")
+}
+
+fn load_geometry() -> Option<WindowGeometry> {
+    let content = fs::read_to_string(state_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Write the last known geometry to disk
+pub fn save_now() -> Result<(), String> {
+    let Some(geometry) = CURRENT.lock().ok().and_then(|g| *g) else {
+        return Ok(());
+    };
+    let path = state_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create window state directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&geometry)
+        .map_err(|e| format!("Failed to serialize window state: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write window_state.json: {}", e))
+}
+
+/// Apply the saved geometry to the main window, then show it (window starts hidden)
+pub fn restore(app: &App) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+    
+    if let Some(geometry) = load_geometry() {
+        let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+        
+        let on_screen = window
+            .available_monitors()
+            .map(|monitors| {
+                monitors.iter().any(|m| {
+                    let (pos, size) = (m.position(), m.size());
+                    let overlap_x = (geometry.x + geometry.width as i32).min(pos.x + size.width as i32) - geometry.x.max(pos.x);
+                    let overlap_y = (geometry.y + geometry.height as i32).min(pos.y + size.height as i32) - geometry.y.max(pos.y);
+                    overlap_x >= MIN_VISIBLE_PX && overlap_y >= MIN_VISIBLE_PX
+                })
+            })
+            .unwrap_or(true);
+        if on_screen {
+            let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+        } else {
+            let _ = window.center();
+        }
+        
+        if geometry.maximized {
+            let _ = window.maximize();
+        }
+        if let Ok(mut current) = CURRENT.lock() {
+            *current = Some(geometry);
+        }
+    }
+    
+    let _ = window.show();
+}
+
+/// Track geometry changes of the main window (registered with Builder::on_window_event)
+pub fn on_window_event<R: Runtime>(window: &Window<R>, event: &WindowEvent) {
+    if window.label() != MAIN_WINDOW {
+        return;
+    }
+    match event {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) => remember(window),
+        WindowEvent::CloseRequested { .. } => {
+            remember(window);
+            if let Err(e) = save_now() {
+                crate::debug_eprintln!("[window_state] Failed to save window state: {}", e);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Update the in-memory geometry. While maximized keep the last normal size/position so
+/// un-maximizing after a restart goes back to it; ignore minimized (off-screen) positions.
+fn remember<R: Runtime>(window: &Window<R>) {
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    let Ok(mut current) = CURRENT.lock() else {
+        return;
+    };
+    
+    if maximized {
+        if let Some(geometry) = current.as_mut() {
+            geometry.maximized = true;
+        }
+        return;
+    }
+    
+    if let (Ok(pos), Ok(size)) = (window.outer_position(), window.inner_size()) {
+        if size.width > 0 && size.height > 0 {
+            *current = Some(WindowGeometry {
+                x: pos.x,
+                y: pos.y,
+                width: size.width,
+                height: size.height,
+                maximized: false,
+            });
+        }
+    }
+}
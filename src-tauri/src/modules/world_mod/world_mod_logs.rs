@@ -6,10 +6,14 @@
 
 use rusqlite::{Connection, Result as SqlResult};
 use rusqlite::OptionalExtension;
+use crate::modules::database::connection::app_db;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
 use tauri::async_runtime;
 
+use crate::modules::log_reader::log_parser;
+use crate::modules::log_reader::patterns;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BanLogEntry {
     pub id: i64,
@@ -21,37 +25,15 @@ pub struct BanLogEntry {
     pub location: String,    // "world_id:instance_id" or "N/A"
 }
 
-/// Get the directory where the database is stored
-/// Uses the same pathing as other modules (LocalAppData\FCHClient on Windows)
-fn db_dir() -> PathBuf {
-    let base = std::env::var("LOCALAPPDATA")
-        .ok()
-        .map(PathBuf::from)
-        .unwrap_or_else(|| PathBuf::from("C:/Users/Public"));
-    base.join("FCHClient")
-}
-
-/// Get the path to the SQLite database file
-fn db_path() -> PathBuf {
-    db_dir().join("world_mod_logs.db")
-}
-
 /// Get or create database connection
 fn get_connection() -> SqlResult<Connection> {
-    let db_path = db_path();
-    
-    // Ensure directory exists (same pattern as local_db module)
-    if let Some(parent) = db_path.parent() {
-        if let Err(e) = std::fs::create_dir_all(parent) {
-            return Err(rusqlite::Error::SqliteFailure(
-                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CANTOPEN),
-                Some(format!("Failed to create directory: {}", e))
-            ));
-        }
-    }
-    
-    let conn = Connection::open(&db_path)?;
-    
+    let conn = app_db("world_mod_logs.db")?;
+    create_schema(&conn)?;
+    Ok(conn)
+}
+
+/// Create the ban_logs table and indexes, migrating older databases
+fn create_schema(conn: &Connection) -> SqlResult<()> {
     // Create table if it doesn't exist
     conn.execute(
         "CREATE TABLE IF NOT EXISTS ban_logs (
@@ -93,7 +75,7 @@ fn get_connection() -> SqlResult<Connection> {
         [],
     )?;
     
-    Ok(conn)
+    Ok(())
 }
 
 /// Initialize the database - creates file and tables if they don't exist
@@ -111,6 +93,17 @@ pub fn add_ban_log(admin: String, target: String, reason: String, timestamp: Str
     let start_time = std::time::Instant::now();
     crate::debug_println!("[PERF] add_ban_log START (action: {}, admin: {}, target: {})", action_type, admin, target);
     
+    // Rows are ordered by string comparison on timestamp, so a malformed one would
+    // sort into the wrong place forever. Near-misses are normalized; anything else is rejected.
+    let timestamp = if patterns::is_valid_log_ts(&timestamp) {
+        timestamp
+    } else if let Some(ts) = normalize_log_ts(&timestamp) {
+        ts
+    } else {
+        crate::debug_eprintln!("[world_mod_logs] Rejected moderation log with invalid timestamp: {:?}", timestamp);
+        return Err(format!("Invalid timestamp (expected YYYY.MM.DD HH:MM:SS): {}", timestamp));
+    };
+    
     let conn_start = std::time::Instant::now();
     let conn = get_connection().map_err(|e| e.to_string())?;
     let conn_duration = conn_start.elapsed();
@@ -121,19 +114,17 @@ pub fn add_ban_log(admin: String, target: String, reason: String, timestamp: Str
     let check_start = std::time::Instant::now();
     
     // Parse the timestamp to check for nearby entries
-    // Format: YYYY.MM.DD HH:MM:SS
-    let parsed_timestamp = chrono::NaiveDateTime::parse_from_str(&timestamp, "%Y.%m.%d %H:%M:%S")
-        .ok();
+    // Format: YYYY.MM.DD HH:MM:SS (interpreted in the configured log timezone)
+    let parsed_timestamp = log_parser::parse_log_ts(&timestamp);
     
     let existing: Option<i64> = if let Some(ts) = parsed_timestamp {
         // Check for entries with same target and reason within 3 seconds
         // We check backwards in time (3 seconds before current timestamp)
         // to find the "first" entry in the 3-second window
         let window_start = ts - chrono::Duration::seconds(3);
-        let window_end = ts; // Current timestamp
         
-        let window_start_str = window_start.format("%Y.%m.%d %H:%M:%S").to_string();
-        let window_end_str = window_end.format("%Y.%m.%d %H:%M:%S").to_string();
+        let window_start_str = log_parser::format_log_ts(&window_start);
+        let window_end_str = timestamp.clone(); // Current timestamp
         
         // Find the earliest entry with same target and reason within the window
         let mut stmt = conn
@@ -296,6 +287,68 @@ pub fn search_ban_logs(query: &str) -> Result<Vec<BanLogEntry>, String> {
     Ok(entries)
 }
 
+/// Count stored moderation entries as (bans, warns)
+pub fn get_ban_log_counts() -> Result<(i64, i64), String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    conn.query_row(
+        "SELECT COALESCE(SUM(action_type = 'warn'), 0), COUNT(*) FROM ban_logs",
+        [],
+        |row| Ok((row.get::<_, i64>(1)? - row.get::<_, i64>(0)?, row.get::<_, i64>(0)?)),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Per-admin ban/warn counts, optionally limited to a timestamp range (YYYY.MM.DD HH:MM:SS, inclusive).
+/// Missing/"N/A" admins are grouped under "Unknown". Sorted by total actions, highest first.
+pub fn get_admin_moderation_stats_between(from_ts: Option<&str>, to_ts: Option<&str>) -> Result<Vec<serde_json::Value>, String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT CASE WHEN admin IS NULL OR TRIM(admin) = '' OR admin = 'N/A' THEN 'Unknown' ELSE admin END AS who,
+                    COALESCE(action_type, 'ban'), COUNT(*)
+             FROM ban_logs
+             WHERE (?1 IS NULL OR timestamp >= ?1) AND (?2 IS NULL OR timestamp <= ?2)
+             GROUP BY who, COALESCE(action_type, 'ban')"
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let rows = stmt
+        .query_map(rusqlite::params![from_ts, to_ts], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    
+    // Fold (admin, action_type) groups into one entry per admin
+    let mut per_admin: HashMap<String, (i64, i64)> = HashMap::new();
+    for (admin, action_type, count) in rows {
+        let counts = per_admin.entry(admin).or_insert((0, 0));
+        if action_type == "warn" {
+            counts.1 += count;
+        } else {
+            counts.0 += count;
+        }
+    }
+    
+    let mut stats: Vec<(String, i64, i64)> = per_admin
+        .into_iter()
+        .map(|(admin, (bans, warns))| (admin, bans, warns))
+        .collect();
+    stats.sort_by(|a, b| (b.1 + b.2).cmp(&(a.1 + a.2)).then_with(|| a.0.cmp(&b.0)));
+    
+    Ok(stats
+        .into_iter()
+        .map(|(admin, bans, warns)| serde_json::json!({
+            "admin": admin,
+            "bans": bans,
+            "warns": warns,
+            "total": bans + warns
+        }))
+        .collect())
+}
+
 /// Send a moderation log entry to the API endpoint.
 /// This is called asynchronously after a successful database insertion
 async fn send_log_to_api(admin: String, target: String, reason: String, action_type: String, location: String) -> Result<(), String> {
@@ -310,8 +363,7 @@ async fn send_log_to_api(admin: String, target: String, reason: String, action_t
         .collect();
     
     // API base URL - should match frontend and other modules
-    let api_base = std::env::var("VITE_API_BASE")
-        .unwrap_or_else(|_| "https://fch-toolkit.com".to_string());
+    let api_base = crate::modules::settings::settings::api_base();
     
     let url = format!("{}/api/worldlogs", api_base);
     
@@ -326,10 +378,11 @@ async fn send_log_to_api(admin: String, target: String, reason: String, action_t
     });
     
     // Send HTTP POST request
-    let client = reqwest::Client::new();
+    let client = crate::modules::http_client::http_client::client();
     let response = client
         .post(&url)
         .json(&payload)
+        .timeout(crate::modules::http_client::http_client::API_TIMEOUT)
         .send()
         .await
         .map_err(|e| format!("HTTP error: {}", e))?;
@@ -342,12 +395,72 @@ async fn send_log_to_api(admin: String, target: String, reason: String, action_t
     Ok(())
 }
 
+/// Rewrite a near-miss timestamp (e.g. "2024.1.5 9:03:07" or surrounding spaces)
+/// into the zero-padded log format. None if it can't be read as a log timestamp at all.
+fn normalize_log_ts(ts: &str) -> Option<String> {
+    chrono::NaiveDateTime::parse_from_str(ts.trim(), log_parser::LOG_TS_FORMAT)
+        .ok()
+        .map(|naive| naive.format(log_parser::LOG_TS_FORMAT).to_string())
+}
+
+/// Find ban_logs rows whose timestamp doesn't match YYYY.MM.DD HH:MM:SS.
+/// With `fix`, rows that can be normalized are rewritten in place; the rest are only reported.
+/// Returns one { id, timestamp, target, normalized, fixed } object per bad row.
+pub fn find_invalid_timestamps(fix: bool) -> Result<Vec<serde_json::Value>, String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    find_invalid_timestamps_in(&conn, fix)
+}
+
+fn find_invalid_timestamps_in(conn: &Connection, fix: bool) -> Result<Vec<serde_json::Value>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, timestamp, target FROM ban_logs ORDER BY id ASC")
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<(i64, String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+    
+    let mut report = Vec::new();
+    for (id, timestamp, target) in rows {
+        if patterns::is_valid_log_ts(&timestamp) {
+            continue;
+        }
+        let normalized = normalize_log_ts(&timestamp);
+        let mut fixed = false;
+        if fix {
+            if let Some(ref ts) = normalized {
+                conn.execute(
+                    "UPDATE ban_logs SET timestamp = ?1 WHERE id = ?2",
+                    rusqlite::params![ts, id],
+                )
+                .map_err(|e| e.to_string())?;
+                fixed = true;
+            }
+        }
+        report.push(serde_json::json!({
+            "id": id,
+            "timestamp": timestamp,
+            "target": target,
+            "normalized": normalized,
+            "fixed": fixed
+        }));
+    }
+    
+    if !report.is_empty() {
+        crate::debug_println!("[world_mod_logs] Found {} moderation log(s) with invalid timestamps", report.len());
+    }
+    Ok(report)
+}
+
 // Tauri commands
 
 #[tauri::command]
 pub fn add_ban_log_entry(admin: String, target: String, reason: String, timestamp: Option<String>, action_type: Option<String>) -> Result<i64, String> {
     // If timestamp not provided, use current time (for manual entries)
-    let ts = timestamp.unwrap_or_else(|| chrono::Local::now().format("%Y.%m.%d %H:%M:%S").to_string());
+    let ts = timestamp
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(log_parser::now_log_ts);
     let action = action_type.unwrap_or_else(|| "ban".to_string());
     add_ban_log(admin, target, reason, ts, action, "N/A".to_string())
 }
@@ -365,3 +478,88 @@ pub fn search_ban_log_entries(query: String) -> Result<Vec<BanLogEntry>, String>
         search_ban_logs(&query)
     }
 }
+
+#[tauri::command]
+pub fn get_admin_moderation_stats(from_ts: Option<String>, to_ts: Option<String>) -> Result<Vec<serde_json::Value>, String> {
+    let from_ts = from_ts.filter(|s| !s.trim().is_empty());
+    let to_ts = to_ts.filter(|s| !s.trim().is_empty());
+    get_admin_moderation_stats_between(from_ts.as_deref(), to_ts.as_deref())
+}
+
+/// Report moderation log rows with malformed timestamps; pass fix = true to normalize
+/// the ones that can be read. Unreadable rows are left alone for manual review.
+#[tauri::command]
+pub fn repair_timestamps(fix: Option<bool>) -> Result<Vec<serde_json::Value>, String> {
+    find_invalid_timestamps(fix.unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_log_ts_zero_pads_fields() {
+        assert_eq!(normalize_log_ts("2024.1.5 9:03:07").as_deref(), Some("2024.01.05 09:03:07"));
+    }
+
+    #[test]
+    fn normalize_log_ts_trims_surrounding_spaces() {
+        assert_eq!(normalize_log_ts("  2024.01.05 09:03:07 ").as_deref(), Some("2024.01.05 09:03:07"));
+    }
+
+    #[test]
+    fn normalize_log_ts_rejects_garbage() {
+        assert_eq!(normalize_log_ts("not a timestamp"), None);
+        assert_eq!(normalize_log_ts(""), None);
+        assert_eq!(normalize_log_ts("2024-01-05T09:03:07"), None);
+        assert_eq!(normalize_log_ts("2024.13.05 09:03:07"), None);
+    }
+
+    #[test]
+    fn find_invalid_timestamps_fixes_readable_rows_only() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        for (target, ts) in [
+            ("good", "2024.01.05 09:03:07"),
+            ("unpadded", "2024.1.5 9:03:07"),
+            ("spaced", " 2024.02.10 10:00:00 "),
+            ("garbage", "yesterday"),
+        ] {
+            conn.execute(
+                "INSERT INTO ban_logs (admin, target, reason, timestamp) VALUES ('admin', ?1, 'reason', ?2)",
+                rusqlite::params![target, ts],
+            )
+            .unwrap();
+        }
+
+        let report = find_invalid_timestamps_in(&conn, true).unwrap();
+        let summary: Vec<(&str, bool)> = report
+            .iter()
+            .map(|r| (r["target"].as_str().unwrap(), r["fixed"].as_bool().unwrap()))
+            .collect();
+        assert_eq!(summary, vec![("unpadded", true), ("spaced", true), ("garbage", false)]);
+        assert!(report[2]["normalized"].is_null());
+
+        let stored: Vec<(String, String)> = conn
+            .prepare("SELECT target, timestamp FROM ban_logs ORDER BY id ASC")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<SqlResult<_>>()
+            .unwrap();
+        assert_eq!(
+            stored,
+            vec![
+                ("good".to_string(), "2024.01.05 09:03:07".to_string()),
+                ("unpadded".to_string(), "2024.01.05 09:03:07".to_string()),
+                ("spaced".to_string(), "2024.02.10 10:00:00".to_string()),
+                ("garbage".to_string(), "yesterday".to_string()),
+            ]
+        );
+
+        // Only the unreadable row is still reported on a second pass
+        let again = find_invalid_timestamps_in(&conn, true).unwrap();
+        assert_eq!(again.len(), 1);
+        assert_eq!(again[0]["target"], "garbage");
+    }
+}